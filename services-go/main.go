@@ -209,14 +209,21 @@ func main() {
 	fmt.Println(tokenSell.Bytes())
 	fmt.Println(signerSellToken.Bytes())
 
+	// burn tx account is inserted after the unshield maker token account by the unshield builder
 	unshieldAccounts := []*solana.AccountMeta{
 		solana.NewAccountMeta(vaultAssTokenAcc, true, false),
 		solana.NewAccountMeta(unshieldMakerRequest, false, false),
 		solana.NewAccountMeta(vaultTokenAuthority, false, false),
-		solana.NewAccountMeta(vaultAcc, true, false),
+		solana.NewAccountMeta(vaultAcc, false, false),
 		solana.NewAccountMeta(incognitoProxy, false, false),
 		solana.NewAccountMeta(solana.TokenProgramID, false, false),
 		solana.NewAccountMeta(unshielMakerAssTokenAcc, true, false),
+		solana.NewAccountMeta(feePayer.PublicKey(), true, true),
+		solana.NewAccountMeta(solana.SystemProgramID, false, false),
+		solana.NewAccountMeta(unshieldMakerRequest, false, false),
+		solana.NewAccountMeta(tokenUnshield, false, false),
+		solana.NewAccountMeta(solana.SPLAssociatedTokenAccountProgramID, false, false),
+		solana.NewAccountMeta(solana.SysVarRentPubkey, false, false),
 	}
 
 	_, err = rpcClient.GetAccountInfo(context.TODO(), unshielMakerAssTokenAcc)
@@ -261,10 +268,16 @@ func main() {
 		solana.NewAccountMeta(vaultAssTokenAcc, true, false),
 		solana.NewAccountMeta(signer.PublicKey(), false, false),
 		solana.NewAccountMeta(vaultTokenAuthority, false, false),
-		solana.NewAccountMeta(vaultAcc, true, false),
+		solana.NewAccountMeta(vaultAcc, false, false),
 		solana.NewAccountMeta(incognitoProxy, false, false),
 		solana.NewAccountMeta(solana.TokenProgramID, false, false),
 		solana.NewAccountMeta(signerSellToken, true, false),
+		solana.NewAccountMeta(feePayer.PublicKey(), true, true),
+		solana.NewAccountMeta(solana.SystemProgramID, false, false),
+		solana.NewAccountMeta(signerTokenAuthority, false, false),
+		solana.NewAccountMeta(tokenSell, false, false),
+		solana.NewAccountMeta(solana.SPLAssociatedTokenAccountProgramID, false, false),
+		solana.NewAccountMeta(solana.SysVarRentPubkey, false, false),
 	}
 	fmt.Println(burnProofdAccounts)
 
@@ -337,8 +350,22 @@ func main() {
 		solana.NewAccountMeta(signerSellToken, true, false),                                                                 // source token acc
 		solana.NewAccountMeta(signerBuyToken, true, false),                                                                  // dest token acc
 		solana.NewAccountMeta(signerTokenAuthority, false, false),                                                           // user owner
-		solana.NewAccountMeta(ammProgramId, false, false),                                                                   // user owner
+		solana.NewAccountMeta(ammProgramId, false, false),                                                                   // dest program
 	}
+	// signer and dest program are not forwarded to the dest program
+	numAcc := len(swapAccounts) - 2
+	allowlist, _, err := solana.FindProgramAddress(
+		[][]byte{[]byte("allowlist")},
+		program,
+	)
+	if err != nil {
+		panic(err)
+	}
+	swapAccounts = append(
+		swapAccounts,
+		solana.NewAccountMeta(incognitoProxy, false, false),
+		solana.NewAccountMeta(allowlist, false, false),
+	)
 
 	// swapbasein
 	tag := byte(9)
@@ -352,7 +379,7 @@ func main() {
 	swapData = append(swapData, amountOutBytes...)
 	data := append([]byte{0x3}, []byte{byte(len(swapData))}...)
 	data = append(data, swapData...)
-	data = append(data, []byte{byte(len(swapAccounts) - 2)}...)
+	data = append(data, []byte{byte(numAcc)}...)
 	data = append(data, []byte{byte(17)}...)
 	fmt.Printf("data %v\n", data)
 	txSwap, err := solana.NewTransaction(
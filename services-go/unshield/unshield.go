@@ -14,6 +14,8 @@ import (
 )
 
 const UNSHIELD_TAG = 0x1
+const BURN_TX_SEED = "burn_tx"
+const BURN_TX_INDEX = 7
 
 type decodedProof struct {
 	Instruction []byte
@@ -44,6 +46,8 @@ type Unshield struct {
 	accounts       []*solana.AccountMeta
 }
 
+// NewUnshield takes the unshield accounts without the burn tx account,
+// which is derived from the tx id of the burn proof and inserted at BURN_TX_INDEX by Build.
 func NewUnshield(burnTx string, getProofMethod string, incFullNode string, programID solana.PublicKey, accounts []*solana.AccountMeta) Unshield {
 	return Unshield{
 		burnTx,
@@ -108,9 +112,26 @@ func (us *Unshield) Build() *solana.GenericInstruction {
 		temp = append(temp, sig...)
 	}
 
+	// burn tx account derived from incognito proxy and tx id of the burn instruction
+	if len(us.accounts) < BURN_TX_INDEX || len(proof.Instruction) < 130 {
+		fmt.Printf("invalid unshield accounts or burn instruction \n")
+		return nil
+	}
+	burnTxAcc, _, err := solana.FindProgramAddress(
+		[][]byte{[]byte(BURN_TX_SEED), us.accounts[4].PublicKey.Bytes(), proof.Instruction[98:130]},
+		us.programID,
+	)
+	if err != nil {
+		fmt.Printf("can not derive burn tx account %v \n", err)
+		return nil
+	}
+	accounts := append([]*solana.AccountMeta{}, us.accounts[:BURN_TX_INDEX]...)
+	accounts = append(accounts, solana.NewAccountMeta(burnTxAcc, true, false))
+	accounts = append(accounts, us.accounts[BURN_TX_INDEX:]...)
+
 	accountSlice := solana.AccountMetaSlice{}
 	err = accountSlice.SetAccounts(
-		us.accounts,
+		accounts,
 	)
 	if err != nil {
 		fmt.Printf("init account slice failed %v \n", err)
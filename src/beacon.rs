@@ -0,0 +1,226 @@
+//! Beacon instructions signed by the Incognito beacon committee.
+
+use std::fmt;
+use solana_program::{
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    secp256k1_recover::{Secp256k1Pubkey, SECP256K1_PUBLIC_KEY_LENGTH},
+};
+use arrayref::{array_ref, array_refs};
+use crate::{error::BridgeError, proof::append_at_top};
+
+/// Meta type of unshield to the receiver's token account.
+pub const UNSHIELD_META_TYPE: u8 = 157;
+/// Meta type of unshield to the token account of the receiver's signer authority.
+pub const UNSHIELD_TO_PDA_META_TYPE: u8 = 158;
+/// Meta type of beacon committee swap.
+pub const SWAP_BEACON_META_TYPE: u8 = 70;
+/// Meta type of quorum threshold update, next to the Solana unshield meta types.
+/// Incognito already uses 71 for `BridgeSwapConfirmMeta`.
+pub const QUORUM_THRESHOLD_META_TYPE: u8 = 159;
+/// Shard id of every beacon instruction handled by the bridge.
+pub const BEACON_SHARD_ID: u8 = 1;
+/// Length of an unshield instruction.
+pub const UNSHIELD_INST_LEN: usize = 1 + 1 + 32 + 32 + 32 + 32 + 32;
+/// Length of a committee swap instruction before its beacon list.
+pub const SWAP_COMMITTEE_LEN: usize = 1 + 1 + 32 + 32;
+/// Length of a quorum threshold instruction.
+pub const QUORUM_THRESHOLD_LEN: usize = 1 + 1 + 32 + 32;
+
+/// Big endian 256 bit unsigned number, as numbers are encoded in beacon instructions.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct U256(pub [u8; 32]);
+
+impl U256 {
+    /// Converts to u64, numbers which overflow u64 are rejected instead of truncated.
+    pub fn to_u64(&self) -> Result<u64, ProgramError> {
+        let (high, low) = array_refs![&self.0, 24, 8];
+        if *high != [0; 24] {
+            msg!("Number overflows u64");
+            return Err(BridgeError::InvalidBeaconInstruction.into());
+        }
+        Ok(u64::from_be_bytes(*low))
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(value: u64) -> Self {
+        U256(append_at_top(value))
+    }
+}
+
+/// Unshield of `amount` of `token` to `receiver`, burnt on Incognito by `tx_id`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UnshieldInstruction {
+    // token mint
+    pub token: Pubkey,
+    // receiver of the unshield
+    pub receiver: Pubkey,
+    // amount in the token's decimals
+    pub amount: U256,
+    // burn tx id on Incognito
+    pub tx_id: [u8; 32],
+    // last 32 bytes, not used by the bridge
+    pub reserved: [u8; 32],
+}
+
+/// Beacon instruction payload, decoded by its meta type.
+#[derive(Clone, PartialEq)]
+pub enum BeaconInstruction {
+    /// Unshield to the receiver's associated token account, meta type 157.
+    Unshield(UnshieldInstruction),
+    /// Unshield to the associated token account of the receiver's signer authority, meta type 158.
+    UnshieldToPda(UnshieldInstruction),
+    /// New beacon committee from `start_height`, meta type 70.
+    SwapBeaconCommittee {
+        /// beacon height the committee starts signing from
+        start_height: U256,
+        /// beacon public keys
+        beacons: Vec<Secp256k1Pubkey>,
+    },
+    /// New quorum threshold `numerator / denominator`, meta type 159.
+    SetQuorumThreshold {
+        /// numerator of the threshold
+        numerator: U256,
+        /// denominator of the threshold
+        denominator: U256,
+    },
+}
+
+// Secp256k1Pubkey does not implement Debug, print beacons as raw bytes
+impl fmt::Debug for BeaconInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unshield(unshield) => f.debug_tuple("Unshield").field(unshield).finish(),
+            Self::UnshieldToPda(unshield) => f.debug_tuple("UnshieldToPda").field(unshield).finish(),
+            Self::SwapBeaconCommittee { start_height, beacons } => f
+                .debug_struct("SwapBeaconCommittee")
+                .field("start_height", start_height)
+                .field("beacons", &beacons.iter().map(|beacon| beacon.0).collect::<Vec<_>>())
+                .finish(),
+            Self::SetQuorumThreshold { numerator, denominator } => f
+                .debug_struct("SetQuorumThreshold")
+                .field("numerator", numerator)
+                .field("denominator", denominator)
+                .finish(),
+        }
+    }
+}
+
+impl BeaconInstruction {
+    /// Decodes a beacon instruction, its meta type and shard id come first.
+    pub fn decode(inst: &[u8]) -> Result<Self, ProgramError> {
+        let (meta_type, shard_id) = match inst {
+            [meta_type, shard_id, ..] => (*meta_type, *shard_id),
+            _ => {
+                msg!("Invalid instruction input");
+                return Err(BridgeError::InvalidBeaconInstruction.into());
+            }
+        };
+        if shard_id != BEACON_SHARD_ID {
+            msg!("Invalid beacon instruction metatype {}, {}", meta_type, shard_id);
+            return Err(BridgeError::InvalidMetaType.into());
+        }
+        Ok(match meta_type {
+            UNSHIELD_META_TYPE | UNSHIELD_TO_PDA_META_TYPE => {
+                if inst.len() != UNSHIELD_INST_LEN {
+                    msg!("Invalid unshield instruction length {}", inst.len());
+                    return Err(BridgeError::InvalidBeaconInstruction.into());
+                }
+                let inst_ = array_ref![inst, 0, UNSHIELD_INST_LEN];
+                #[allow(clippy::ptr_offset_with_cast)]
+                let (_, _, token, receiver, amount, tx_id, reserved) = array_refs![inst_, 1, 1, 32, 32, 32, 32, 32];
+                let unshield = UnshieldInstruction {
+                    token: Pubkey::new_from_array(*token),
+                    receiver: Pubkey::new_from_array(*receiver),
+                    amount: U256(*amount),
+                    tx_id: *tx_id,
+                    reserved: *reserved,
+                };
+                if meta_type == UNSHIELD_META_TYPE {
+                    Self::Unshield(unshield)
+                } else {
+                    Self::UnshieldToPda(unshield)
+                }
+            }
+            SWAP_BEACON_META_TYPE => {
+                if inst.len() < SWAP_COMMITTEE_LEN {
+                    msg!("Invalid instruction input");
+                    return Err(BridgeError::InvalidBeaconInstruction.into());
+                }
+                let inst_ = array_ref![inst, 0, SWAP_COMMITTEE_LEN];
+                #[allow(clippy::ptr_offset_with_cast)]
+                let (_, _, start_height, num_vals) = array_refs![inst_, 1, 1, 32, 32];
+                let num_vals = U256(*num_vals).to_u64()?;
+                let beacons_len = usize::try_from(num_vals)
+                    .ok()
+                    .and_then(|num_vals| num_vals.checked_mul(SECP256K1_PUBLIC_KEY_LENGTH));
+                if beacons_len != Some(inst.len() - SWAP_COMMITTEE_LEN) {
+                    msg!("Invalid number of beacons {}", num_vals);
+                    return Err(BridgeError::InvalidBeaconInstruction.into());
+                }
+                Self::SwapBeaconCommittee {
+                    start_height: U256(*start_height),
+                    beacons: inst[SWAP_COMMITTEE_LEN..]
+                        .chunks(SECP256K1_PUBLIC_KEY_LENGTH)
+                        .map(Secp256k1Pubkey::new)
+                        .collect(),
+                }
+            }
+            QUORUM_THRESHOLD_META_TYPE => {
+                if inst.len() != QUORUM_THRESHOLD_LEN {
+                    msg!("Invalid instruction input");
+                    return Err(BridgeError::InvalidBeaconInstruction.into());
+                }
+                let inst_ = array_ref![inst, 0, QUORUM_THRESHOLD_LEN];
+                #[allow(clippy::ptr_offset_with_cast)]
+                let (_, _, numerator, denominator) = array_refs![inst_, 1, 1, 32, 32];
+                Self::SetQuorumThreshold {
+                    numerator: U256(*numerator),
+                    denominator: U256(*denominator),
+                }
+            }
+            _ => {
+                msg!("Invalid beacon instruction metatype {}, {}", meta_type, shard_id);
+                return Err(BridgeError::InvalidMetaType.into());
+            }
+        })
+    }
+
+    /// Encodes the beacon instruction, `decode` of the output returns the same value.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut inst = vec![self.meta_type(), BEACON_SHARD_ID];
+        match self {
+            Self::Unshield(unshield) | Self::UnshieldToPda(unshield) => {
+                inst.extend_from_slice(unshield.token.as_ref());
+                inst.extend_from_slice(unshield.receiver.as_ref());
+                inst.extend_from_slice(&unshield.amount.0);
+                inst.extend_from_slice(&unshield.tx_id);
+                inst.extend_from_slice(&unshield.reserved);
+            }
+            Self::SwapBeaconCommittee { start_height, beacons } => {
+                inst.extend_from_slice(&start_height.0);
+                inst.extend_from_slice(&U256::from(beacons.len() as u64).0);
+                for beacon in beacons {
+                    inst.extend_from_slice(&beacon.to_bytes());
+                }
+            }
+            Self::SetQuorumThreshold { numerator, denominator } => {
+                inst.extend_from_slice(&numerator.0);
+                inst.extend_from_slice(&denominator.0);
+            }
+        }
+        inst
+    }
+
+    /// Meta type identifying the instruction on Incognito.
+    pub fn meta_type(&self) -> u8 {
+        match self {
+            Self::Unshield(_) => UNSHIELD_META_TYPE,
+            Self::UnshieldToPda(_) => UNSHIELD_TO_PDA_META_TYPE,
+            Self::SwapBeaconCommittee { .. } => SWAP_BEACON_META_TYPE,
+            Self::SetQuorumThreshold { .. } => QUORUM_THRESHOLD_META_TYPE,
+        }
+    }
+}
@@ -72,6 +72,123 @@ pub enum BridgeError {
     /// Invalid meta type.
     #[error("Invalid meta type")]
     InvalidMetaType,
+    /// New committee must start after the current one.
+    #[error("Invalid committee start height")]
+    InvalidCommitteeStartHeight,
+    /// Beacon index used by more than one signature.
+    #[error("Duplicate beacon index")]
+    DuplicateBeaconIndex,
+    /// Beacon indexes must be in ascending order.
+    #[error("Beacon indexes not sorted")]
+    UnsortedBeaconIndexes, // 25
+    /// Beacon index outside of the committee.
+    #[error("Beacon index out of range")]
+    BeaconIndexOutOfRange,
+    /// Incognito address is not valid utf8.
+    #[error("Invalid incognito address")]
+    InvalidIncognitoAddress,
+    /// Log data is not a bridge event.
+    #[error("Invalid event data")]
+    InvalidEventData,
+    /// Inst paths and is lefts have different lengths.
+    #[error("Inst path length mismatch")]
+    InstPathLengthMismatch,
+    /// Indexes and signatures have different lengths.
+    #[error("Signature length mismatch")]
+    SignatureLengthMismatch, // 30
+    /// Unexpected bytes after the instruction data.
+    #[error("Trailing instruction data")]
+    TrailingInstructionData,
+    /// Bridge paused by guardian.
+    #[error("Bridge is paused")]
+    BridgePaused,
+    /// Signer is not the guardian of the incognito proxy.
+    #[error("Invalid guardian")]
+    InvalidGuardian,
+    /// Destination program not in the dapp allowlist.
+    #[error("Program not allowed")]
+    ProgramNotAllowed,
+    /// Dapp allowlist cannot hold more programs.
+    #[error("Allowlist full")]
+    AllowlistFull, // 35
+    /// Program already in the dapp allowlist.
+    #[error("Program already allowed")]
+    ProgramAlreadyAllowed,
+    /// Allowlist account not derived from the incognito proxy.
+    #[error("Invalid allowlist account")]
+    InvalidAllowlistAccount,
+    /// Program called by dapp interaction failed with a builtin error.
+    #[error("Dapp interaction failed")]
+    DappInteractionFailed,
+    /// Dapp call refers to an account outside of the forwarded accounts.
+    #[error("Invalid dapp call")]
+    InvalidDappCall,
+    /// Token balance after dapp interaction below the requested minimum.
+    #[error("Balance below minimum")]
+    BalanceBelowMinimum, // 40
+    /// Dapp interaction spent more than the requested maximum.
+    #[error("Spend above maximum")]
+    SpendAboveMaximum,
+    /// Account expected to be writable.
+    #[error("Account not writable")]
+    AccountNotWritable,
+    /// Token program account is not the spl token program.
+    #[error("Invalid token program")]
+    InvalidTokenProgram,
+    /// System program account is not the system program.
+    #[error("Invalid system program")]
+    InvalidSystemProgram,
+    /// Vault authority not derived from the incognito proxy.
+    #[error("Invalid vault authority")]
+    InvalidVaultAuthority, // 45
+    /// Vault token account not owned by the vault authority.
+    #[error("Invalid vault token account")]
+    InvalidVaultTokenAccount,
+    /// Associated token program account is not the associated token account program.
+    #[error("Invalid associated token program")]
+    InvalidAssociatedTokenProgram,
+    /// Quorum threshold below 1/2 or unreachable by the full committee.
+    #[error("Invalid quorum threshold")]
+    InvalidQuorumThreshold,
+    /// Quorum threshold update from a beacon height not above the last update.
+    #[error("Stale quorum threshold")]
+    StaleQuorumThreshold,
+    /// Beacon list larger than the incognito proxy account can hold.
+    #[error("Beacon capacity exceeded")]
+    BeaconCapacityExceeded, // 50
+    /// Incognito proxy account written with an unknown layout version.
+    #[error("Unsupported proxy version")]
+    UnsupportedProxyVersion,
+    /// Proof buffer written or used by another authority.
+    #[error("Invalid proof buffer authority")]
+    InvalidProofBufferAuthority,
+    /// Proof chunk written past the end of the proof buffer.
+    #[error("Proof chunk out of bounds")]
+    ProofChunkOutOfBounds,
+    /// Verified root account not derived from the incognito proxy and instruction root.
+    #[error("Invalid verified root account")]
+    InvalidVerifiedRootAccount,
+    /// Instruction root of the beacon block already stored.
+    #[error("Beacon block already verified")]
+    BeaconBlockAlreadyVerified, // 55
+    /// No verified root stored for the instruction root.
+    #[error("Beacon block not verified")]
+    BeaconBlockNotVerified,
+    /// No Secp256k1 program instruction right before the unshield.
+    #[error("Missing secp256k1 instruction")]
+    MissingSecp256k1Instruction,
+    /// Secp256k1 program instruction does not check the beacon block signatures.
+    #[error("Invalid secp256k1 instruction")]
+    InvalidSecp256k1Instruction,
+    /// Upgrade authority of the program did not sign or program data account mismatch.
+    #[error("Invalid upgrade authority")]
+    InvalidUpgradeAuthority,
+    /// Incognito proxy resize growing the account by more than `MAX_PERMITTED_DATA_INCREASE` bytes.
+    #[error("Proxy growth exceeded")]
+    ProxyGrowthExceeded, // 60
+    /// Unshield proven at a beacon height before the current committee started.
+    #[error("Unshield before committee start height")]
+    UnshieldBeforeStartHeight,
 }
 
 impl From<BridgeError> for ProgramError {
@@ -84,4 +201,22 @@ impl<T> DecodeError<T> for BridgeError {
     fn type_of() -> &'static str {
         "Bridge Error"
     }
-}
\ No newline at end of file
+}
+/// Custom errors of the program called by dapp interaction are returned above this offset.
+pub const DAPP_ERROR_OFFSET: u32 = 0x8000_0000;
+
+/// Maps the error of the program called by dapp interaction, keeping its custom error code.
+pub fn dapp_error(err: ProgramError) -> ProgramError {
+    match err {
+        ProgramError::Custom(code) if code < DAPP_ERROR_OFFSET => ProgramError::Custom(DAPP_ERROR_OFFSET + code),
+        _ => BridgeError::DappInteractionFailed.into(),
+    }
+}
+
+/// Custom error code of the program called by dapp interaction, if the error came from it.
+pub fn dapp_error_code(err: &ProgramError) -> Option<u32> {
+    match err {
+        ProgramError::Custom(code) if *code >= DAPP_ERROR_OFFSET => Some(code - DAPP_ERROR_OFFSET),
+        _ => None,
+    }
+}
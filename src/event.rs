@@ -0,0 +1,88 @@
+use solana_program::{
+    log::sol_log_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::str;
+use crate::error::BridgeError;
+
+/// Events emitted by the bridge as borsh encoded `Program data:` logs.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub enum BridgeEvent {
+    /// Token locked in the vault to mint pToken on Incognito
+    Shield {
+        incognito_proxy: Pubkey,
+        inc_address: String,
+        token: Pubkey,
+        amount: u64,
+    },
+    /// Token moved back from a signer authority to the vault to mint pToken on Incognito
+    Withdraw {
+        incognito_proxy: Pubkey,
+        signer: Pubkey,
+        inc_address: String,
+        token: Pubkey,
+        amount: u64,
+    },
+    /// Token released from the vault for a burn tx on Incognito
+    Unshield {
+        incognito_proxy: Pubkey,
+        tx_id: [u8; 32],
+        meta_type: u8,
+        receiver: Pubkey,
+        token: Pubkey,
+        amount: u64,
+    },
+    /// Beacon committee replaced
+    CommitteeUpdated {
+        incognito_proxy: Pubkey,
+        start_height: u64,
+        beacons: Vec<[u8; 64]>,
+    },
+    /// Bridge paused or unpaused by guardian
+    PauseUpdated {
+        incognito_proxy: Pubkey,
+        is_paused: bool,
+    },
+    /// Destination program approved for dapp interaction
+    DappProgramAdded {
+        incognito_proxy: Pubkey,
+        program: Pubkey,
+    },
+    /// Destination program revoked for dapp interaction
+    DappProgramRemoved {
+        incognito_proxy: Pubkey,
+        program: Pubkey,
+    },
+    /// Quorum threshold replaced by the beacon committee
+    QuorumThresholdUpdated {
+        incognito_proxy: Pubkey,
+        numerator: u8,
+        denominator: u8,
+    },
+    /// Incognito proxy account resized to hold `capacity` beacons
+    ProxyResized {
+        incognito_proxy: Pubkey,
+        capacity: u8,
+    },
+}
+
+impl BridgeEvent {
+    /// Logs the event with `sol_log_data`.
+    pub fn emit(&self) {
+        sol_log_data(&[&self.try_to_vec().unwrap()]);
+    }
+}
+
+/// Decodes the data of a `Program data:` log emitted by the bridge.
+pub fn decode_event(data: &[u8]) -> Result<BridgeEvent, ProgramError> {
+    BridgeEvent::try_from_slice(data).map_err(|_| BridgeError::InvalidEventData.into())
+}
+
+/// Incognito payment address provided in a shield request.
+pub fn inc_address_to_string(inc_address: &[u8; 148]) -> Result<String, ProgramError> {
+    str::from_utf8(inc_address)
+        .map(str::to_string)
+        .map_err(|_| BridgeError::InvalidIncognitoAddress.into())
+}
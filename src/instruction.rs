@@ -1,22 +1,43 @@
 use solana_program::{
+    bpf_loader_upgradeable,
     program_error::ProgramError,
     msg,
     pubkey::{Pubkey, PUBKEY_BYTES},
     secp256k1_recover::{Secp256k1Pubkey},
     instruction::{AccountMeta, Instruction},
+    secp256k1_program,
+    system_program,
+    sysvar,
 };
+use arrayref::array_ref;
+use spl_associated_token_account::get_associated_token_address;
+use crate::beacon::UNSHIELD_TO_PDA_META_TYPE;
+use crate::proof::beacon_block_message;
+use crate::quorum::{beacon_eth_address, SECP256K1_SIGNATURE_OFFSETS_LEN};
 use crate::error::BridgeError::{
     InvalidInstruction,
     InstructionUnpackError
 };
 use crate::state::{
     UnshieldRequest,
-    IncognitoProxy,
+    InitBeaconRequest,
     DappRequest,
+    BalanceAssertion,
+    DappCall,
+    BatchDappRequest,
+    QuorumThreshold,
+    BeaconInstructionRequest,
+    BeaconBlockRequest,
+    VerifiedUnshieldRequest,
+    Secp256k1UnshieldRequest,
+    BURN_TX_SEED,
+    VERIFIED_ROOT_SEED,
+    ALLOWLIST_SEED,
 };
 use std::{convert::TryInto, mem::size_of};
 use crate::error::BridgeError;
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum BridgeInstruction {
 
     ///   Request new shield to move token from Solana -> Incognito.
@@ -37,29 +58,49 @@ pub enum BridgeInstruction {
     ///   0. `[writable]` Vault token account to transfer tokens to unshield maker
     ///   1. `[]` Unshield maker address
     ///   2. `[]` $vault_authority derived from `create_program_address(&[incognito proxy account])`
-    ///   3. `[writable]` Vault account to store transaction burn id
+    ///   3. `[]` Vault account which stores legacy transaction burn ids
     ///   4. `[]` Incognito proxy which stores beacon list and bump seed to retrieve vault token account
     ///   5. `[]` Spl Token program id
-    ///   6. `[writable]` Associated token account of unshield maker
+    ///   6. `[writable]` Associated token account of unshield maker, created when it does not exist yet
+    ///   7. `[writable]` $burn_tx derived from `find_program_address(&[BURN_TX_SEED, incognito proxy account, tx id])`
+    ///   8. `[writable, signer]` Payer for the burn tx account and associated token account rent
+    ///   9. `[]` System program id
+    ///   10. `[]` Owner of the receiver token account, the unshield maker or its $signer_authority for meta type 158
+    ///   11. `[]` Token mint to unshield
+    ///   12. `[]` Associated token account program id
+    ///   13. `[]` $SYSVAR_RENT_PUBKEY to create the associated token account
     UnShield {
         /// unshield info
         unshield_info: UnshieldRequest,
     },
 
     ///   Initializes a new Incognito proxy account.
+    ///   The beacon capacity of the proxy is set by its size, see `IncognitoProxy::space`.
     ///
     ///   0. `[]` $SYSVAR_RENT_PUBKEY to check account rent exempt
     ///   1. `[writable]` Incognito proxy account
     ///   2. `[writable]` Vault account
+    ///   3. `[signer]` Guardian allowed to pause the bridge
+    ///
+    ///   Encoded with its quorum threshold under tag 24, instructions under tag 2 carry no
+    ///   threshold and default to 2/3.
     InitBeacon {
         /// beacon info
-        init_beacon_info: IncognitoProxy,
+        init_beacon_info: InitBeaconRequest,
     },
 
     /// Generic instruction to allow vault interact with any dapp on Solana.
+    /// Return data set by the destination program is forwarded as the bridge return data,
+    /// custom errors of the destination program are returned above `DAPP_ERROR_OFFSET`.
+    /// Requests with more than 255 bytes of data or accounts, or with balance assertions on forwarded
+    /// token accounts checked after the call, are encoded with wide lengths under tag 12.
     ///
     /// 0. `[signer]` Signer account
-    /// ...
+    /// 1. `num_acc` accounts forwarded to the destination program, $signer_authority derived
+    ///    from `create_program_address(&[signer account])` signs at `sign_index`
+    /// 2. `[]` Destination program id
+    /// 3. `[]` Incognito proxy the allowlist is bound to, to check the bridge is not paused
+    /// 4. `[]` $allowlist derived from `find_program_address(&[ALLOWLIST_SEED])`
     DappInteraction {
         /// beacon info
         dapp_request: DappRequest,
@@ -78,6 +119,210 @@ pub enum BridgeInstruction {
         amount: u64,
         inc_address: [u8; 148],
     },
+
+    ///   Replace the beacon committee with a new one signed by the current committee.
+    ///
+    ///   0. `[writable]` Incognito proxy which stores beacon list
+    SwapBeaconCommittee {
+        /// swap committee info
+        swap_info: BeaconInstructionRequest,
+    },
+
+    ///   Request new shield of native SOL, wrapped into the vault's native mint token account.
+    ///
+    ///   0. `[writable, signer]` Shield maker address which pays the lamports
+    ///   1. `[writable]` Vault native mint token account to receive lamports from asker
+    ///   2. `[]` Incognito proxy which stores beacon list and bump seed to retrieve vault token account
+    ///   3. `[]` System program id
+    ///   4. `[]` Spl Token program id
+    ShieldNative {
+        /// shield info
+        amount: u64,
+        inc_address: [u8; 148],
+    },
+
+    ///   Disable shield, unshield, withdraw and dapp interaction.
+    ///
+    ///   0. `[writable]` Incognito proxy account
+    ///   1. `[signer]` Guardian of the incognito proxy
+    Pause,
+
+    ///   Enable the bridge again after a pause.
+    ///
+    ///   0. `[writable]` Incognito proxy account
+    ///   1. `[signer]` Guardian of the incognito proxy
+    Unpause,
+
+    ///   Approve a destination program for dapp interaction, the allowlist is created by `InitAllowlist`.
+    ///
+    ///   0. `[writable]` $allowlist derived from `find_program_address(&[ALLOWLIST_SEED])`
+    ///   1. `[]` Incognito proxy the allowlist is bound to
+    ///   2. `[signer]` Guardian of the incognito proxy
+    AddAllowedProgram {
+        /// program to approve
+        program: Pubkey,
+    },
+
+    ///   Revoke a destination program for dapp interaction.
+    ///
+    ///   0. `[writable]` $allowlist derived from `find_program_address(&[ALLOWLIST_SEED])`
+    ///   1. `[]` Incognito proxy the allowlist is bound to
+    ///   2. `[signer]` Guardian of the incognito proxy
+    RemoveAllowedProgram {
+        /// program to revoke
+        program: Pubkey,
+    },
+
+    ///   Batched dapp interaction, calls are executed in order and signed by the same signer authority.
+    ///   Each call selects its program and accounts among the forwarded accounts by index.
+    ///   Requests with more than 255 accounts or bytes of call data are encoded with wide lengths under tag 27.
+    ///
+    /// 0. `[signer]` Signer account
+    /// 1. `num_acc` accounts forwarded to the destination programs, including the programs, $signer_authority
+    ///    derived from `create_program_address(&[signer account])` signs at `sign_index`
+    /// 2. `[]` Incognito proxy the allowlist is bound to, to check the bridge is not paused
+    /// 3. `[]` $allowlist derived from `find_program_address(&[ALLOWLIST_SEED])`
+    BatchDappInteraction {
+        /// calls to execute
+        batch_request: BatchDappRequest,
+    },
+
+    ///   Unshield with meta type 158 into the signer authority of the unshield maker
+    ///   and call a dapp as that authority in the same instruction.
+    ///
+    ///   0-13. Accounts of `UnShield`, with the unshield maker as `[signer]`
+    ///   14.. Accounts of `DappInteraction`, with the unshield maker as signer account
+    UnshieldAndCall {
+        /// burn proof
+        unshield_info: UnshieldRequest,
+        /// dapp call made after the unshield
+        dapp_request: DappRequest,
+    },
+
+    ///   Withdraw the full balance of signer authority token accounts to shield back to Incognito.
+    ///   Accounts 4 and 5 are repeated for each token to withdraw.
+    ///
+    ///   0. `[]` Incognito proxy which stores beacon list and bump seed to retrieve vault token accounts
+    ///   1. `[signer]` signer request address, `[writable]` to receive rent of closed accounts
+    ///   2. `[]` $signer_authority derived from `create_program_address(&[signer account])`
+    ///   3. `[]` Spl Token program id
+    ///   4. `[writable]` Signer authority token account to withdraw
+    ///   5. `[writable]` Vault token account of the same token
+    WithdrawAll {
+        /// incognito address to receive pTokens
+        inc_address: [u8; 148],
+        /// close the emptied token accounts
+        close_accounts: bool,
+    },
+
+    ///   Replace the quorum threshold with one signed by the current committee.
+    ///   The proof is encoded as for `SwapBeaconCommittee`.
+    ///
+    ///   0. `[writable]` Incognito proxy which stores the quorum threshold
+    SetQuorumThreshold {
+        /// quorum threshold info
+        threshold_info: BeaconInstructionRequest,
+    },
+
+    ///   Resize the incognito proxy account to hold `capacity` beacons.
+    ///   The account grows by at most `MAX_PERMITTED_DATA_INCREASE` bytes per instruction,
+    ///   larger capacities are reached by resizing in several steps.
+    ///
+    ///   0. `[writable]` Incognito proxy account
+    ///   1. `[signer]` Guardian of the incognito proxy
+    ///   2. `[writable, signer]` Payer for the additional rent, refunded the rent in excess of the new size
+    ///   3. `[]` System program id
+    ResizeProxy {
+        /// number of beacons the proxy can hold
+        capacity: u8,
+    },
+
+    ///   Initializes a buffer for an unshield proof too large for a single transaction.
+    ///   The buffer must be created zeroed in the same transaction with `ProofBuffer::space(proof length)` bytes,
+    ///   see `BridgeInstruction::pack_unshield_proof` for the proof encoding.
+    ///
+    ///   0. `[]` $SYSVAR_RENT_PUBKEY to check account rent exempt
+    ///   1. `[writable]` Proof buffer account owned by the program
+    ///   2. `[signer]` Authority allowed to write and use the proof
+    InitProofBuffer,
+
+    ///   Write a chunk of the unshield proof into a proof buffer.
+    ///
+    ///   0. `[writable]` Proof buffer account
+    ///   1. `[signer]` Authority of the proof buffer
+    WriteProofChunk {
+        /// offset of the chunk in the proof
+        offset: u32,
+        /// chunk of the proof, with u16 length
+        data: Vec<u8>,
+    },
+
+    ///   Unshield with the proof stored in a proof buffer, then close the buffer.
+    ///
+    ///   0. `[writable]` Proof buffer account
+    ///   1. `[writable, signer]` Authority of the proof buffer which receives its rent
+    ///   2. to 15. Accounts of `UnShield`
+    UnshieldFromBuffer,
+
+    ///   Verify the committee signatures of a beacon block once and store its instruction root,
+    ///   so unshields from the block only prove the merkle inclusion with `UnshieldVerified`.
+    ///
+    ///   0. `[]` Incognito proxy which stores beacon list
+    ///   1. `[writable]` $verified_root derived from `find_program_address(&[VERIFIED_ROOT_SEED, incognito proxy account, inst_root])`
+    ///   2. `[writable, signer]` Payer for the verified root account rent
+    ///   3. `[]` System program id
+    VerifyBeaconBlock {
+        /// signed beacon block
+        block_info: BeaconBlockRequest,
+    },
+
+    ///   Unshield from a beacon block verified with `VerifyBeaconBlock`.
+    ///
+    ///   0-13. Accounts of `UnShield`
+    ///   14. `[]` $verified_root of the instruction root the unshield instruction is proven against
+    UnshieldVerified {
+        /// unshield instruction and merkle path
+        unshield_info: VerifiedUnshieldRequest,
+    },
+
+    ///   Unshield with the beacon signatures checked by the Secp256k1 program instruction
+    ///   right before this instruction in the transaction, see `unshield_secp256k1`.
+    ///
+    ///   0-13. Accounts of `UnShield`
+    ///   14. `[]` Instructions sysvar
+    UnshieldSecp256k1 {
+        /// unshield instruction, merkle path and signing beacons
+        unshield_info: Secp256k1UnshieldRequest,
+    },
+
+    ///   Create the allowlist of the program and bind it to the incognito proxy holding user funds,
+    ///   only this proxy's guardian manages it and only this proxy's pause applies to dapp interaction.
+    ///
+    ///   0. `[writable]` $allowlist derived from `find_program_address(&[ALLOWLIST_SEED])`
+    ///   1. `[]` Incognito proxy account
+    ///   2. `[signer]` Upgrade authority of the program
+    ///   3. `[]` Program data account of the program
+    ///   4. `[writable, signer]` Payer for the allowlist account rent
+    ///   5. `[]` System program id
+    InitAllowlist,
+
+    ///   Rewrite an incognito proxy created before the layout was versioned with the current layout,
+    ///   keeping its vault and beacons and setting its guardian.
+    ///
+    ///   0. `[]` $SYSVAR_RENT_PUBKEY to compute the rent of the grown account
+    ///   1. `[writable]` Incognito proxy account with the unversioned layout
+    ///   2. `[signer]` Guardian allowed to pause the bridge
+    ///   3. `[signer]` Upgrade authority of the program
+    ///   4. `[]` Program data account of the program
+    ///   5. `[writable, signer]` Payer for the additional rent
+    ///   6. `[]` System program id
+    MigrateProxy,
+
+    ///   Close a proof buffer which is no longer needed, its rent goes back to its authority.
+    ///
+    ///   0. `[writable]` Proof buffer account
+    ///   1. `[writable, signer]` Authority of the proof buffer which receives its rent
+    CloseProofBuffer,
 }
 
 impl BridgeInstruction {
@@ -85,71 +330,37 @@ impl BridgeInstruction {
     pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
         let (tag, rest) = input.split_first().ok_or(InvalidInstruction)?;
         Ok(match tag {
-            0 | 4 => {
+            // instructions without data
+            7 | 8 | 17 | 19 | 23 | 25 | 26 if !rest.is_empty() => {
+                return Err(BridgeError::TrailingInstructionData.into());
+            }
+            0 | 4 | 6 => {
                 let (amount, rest) = Self::unpack_u64(rest)?;
-                let (inc_address, _) = Self::unpack_bytes148(rest)?;
-                if *tag == 0 {
-                    Self::Shield {
+                let (inc_address, rest) = Self::unpack_bytes148(rest)?;
+                if !rest.is_empty() {
+                    return Err(BridgeError::TrailingInstructionData.into());
+                }
+                match *tag {
+                    0 => Self::Shield {
                         amount,
                         inc_address: inc_address.clone()
-                    }
-                } else {
-                    Self::WithdrawRequest {
+                    },
+                    4 => Self::WithdrawRequest {
                         amount,
                         inc_address: inc_address.clone()
-                    }
+                    },
+                    _ => Self::ShieldNative {
+                        amount,
+                        inc_address: *inc_address
+                    },
                 }
             },
             1 => {
-                let (inst, rest) =  Self::unpack_bytes162(rest)?;
-                let (height, rest) = Self::unpack_u64(rest)?;
-                let (inst_paths_len,mut rest) = Self::unpack_u8(rest)?;
-                let mut inst_paths = Vec::with_capacity(inst_paths_len as usize + 1);
-                for _ in 0..inst_paths_len {
-                    let (inst_node, rest_) = Self::unpack_bytes32(rest)?;
-                    rest = rest_;
-                    inst_paths.push(*inst_node);
-                }
-                let (inst_paths_is_left_len,mut rest) = Self::unpack_u8(rest)?;
-                let mut inst_path_is_lefts = Vec::with_capacity(inst_paths_is_left_len as usize + 1);
-                for _ in 0..inst_paths_is_left_len {
-                    let (inst_paths_is_left, rest_) = Self::unpack_bool(rest)?;
-                    rest = rest_;
-                    inst_path_is_lefts.push(inst_paths_is_left);
-                }
-                let (inst_root, rest) = Self::unpack_bytes32(rest)?;
-                let (blk_data, rest) = Self::unpack_bytes32(rest)?;
-                let (indexes_len,mut rest) = Self::unpack_u8(rest)?;
-                let mut indexes = Vec::with_capacity(indexes_len as usize + 1);
-                for _ in 0..indexes_len {
-                    let (index, rest_) = Self::unpack_u8(rest)?;
-                    rest = rest_;
-                    indexes.push(index);
-                }
-                let (signature_len,mut rest) = Self::unpack_u8(rest)?;
-                let mut signatures = Vec::with_capacity(signature_len as usize + 1);
-                for _ in 0..signature_len {
-                    let (signature, rest_) = Self::unpack_bytes65(rest)?;
-                    rest = rest_;
-                    signatures.push(*signature);
-                }
-
-                let incognito_proof = UnshieldRequest {
-                    inst: *inst,
-                    height,
-                    inst_paths,
-                    inst_path_is_lefts,
-                    inst_root: *inst_root,
-                    blk_data: *blk_data,
-                    indexes,
-                    signatures,
-                };
-
                 Self::UnShield {
-                    unshield_info: incognito_proof,
+                    unshield_info: Self::unpack_unshield_request(rest)?,
                 }
             },
-            2 => {
+            2 | 24 => {
                 let (vault_key, rest) = Self::unpack_pubkey(rest)?;
                 let (bump_seed, rest) = Self::unpack_u8(rest)?;
                 let (beacon_list_len, mut rest) =  Self::unpack_u8(rest)?;
@@ -160,32 +371,278 @@ impl BridgeInstruction {
                     let new_beacon = Secp256k1Pubkey::new(beacon);
                     beacons.push(new_beacon);
                 }
+                // instructions built before the threshold was configurable are encoded under tag 2
+                let quorum_threshold = if *tag == 2 {
+                    QuorumThreshold::default()
+                } else {
+                    let (numerator, rest_) = Self::unpack_u8(rest)?;
+                    let (denominator, rest_) = Self::unpack_u8(rest_)?;
+                    rest = rest_;
+                    QuorumThreshold { numerator, denominator }
+                };
+                if !rest.is_empty() {
+                    return Err(BridgeError::TrailingInstructionData.into());
+                }
                 Self::InitBeacon {
-                    init_beacon_info: IncognitoProxy{
-                        is_initialized: true,
-                        bump_seed,
+                    init_beacon_info: InitBeaconRequest {
                         vault: vault_key,
-                        beacons
-                    }   
+                        bump_seed,
+                        quorum_threshold,
+                        beacons,
+                    }
                 }
             },
             3 => {
-                let (inst_len, rest) = Self::unpack_u8(rest)?; // todo upgrade inst length
-                let (inst_data, rest) = Self::unpack_nbytes(rest, inst_len)?;
+                let (inst_len, rest) = Self::unpack_u8(rest)?;
+                let (inst_data, rest) = Self::unpack_nbytes(rest, inst_len as usize)?;
                 let (acc_len, rest) = Self::unpack_u8(rest)?;
-                let (sign_index, _) = Self::unpack_u8(rest)?;
+                let (sign_index, rest) = Self::unpack_u8(rest)?;
+                if !rest.is_empty() {
+                    return Err(BridgeError::TrailingInstructionData.into());
+                }
                 Self::DappInteraction {
                     dapp_request: DappRequest {
                         inst: inst_data.to_vec(),
-                        num_acc: acc_len,
-                        sign_index
+                        num_acc: acc_len.into(),
+                        sign_index: sign_index.into(),
+                        ..DappRequest::default()
+                    }
+                }
+            }
+            5 => {
+                Self::SwapBeaconCommittee {
+                    swap_info: Self::unpack_beacon_instruction_request(rest)?,
+                }
+            }
+            7 => Self::Pause,
+            8 => Self::Unpause,
+            9 | 10 => {
+                let (program, rest) = Self::unpack_pubkey(rest)?;
+                if !rest.is_empty() {
+                    return Err(BridgeError::TrailingInstructionData.into());
+                }
+                if *tag == 9 {
+                    Self::AddAllowedProgram { program }
+                } else {
+                    Self::RemoveAllowedProgram { program }
+                }
+            }
+            11 | 27 => {
+                let wide = *tag == 27;
+                let (num_acc, rest) = Self::unpack_index(rest, wide)?;
+                let (sign_index, rest) = Self::unpack_index(rest, wide)?;
+                let (num_calls, mut rest) = Self::unpack_u8(rest)?;
+                let mut calls = Vec::with_capacity(num_calls as usize);
+                for _ in 0..num_calls {
+                    let (call, next) = Self::unpack_dapp_call(rest, wide)?;
+                    calls.push(call);
+                    rest = next;
+                }
+                if !rest.is_empty() {
+                    return Err(BridgeError::TrailingInstructionData.into());
+                }
+                Self::BatchDappInteraction {
+                    batch_request: BatchDappRequest {
+                        calls,
+                        num_acc,
+                        sign_index,
                     }
                 }
             }
+            12 => {
+                let (dapp_request, rest) = Self::unpack_wide_dapp_request(rest)?;
+                if !rest.is_empty() {
+                    return Err(BridgeError::TrailingInstructionData.into());
+                }
+                Self::DappInteraction { dapp_request }
+            }
+            13 => {
+                let (dapp_request, rest) = Self::unpack_wide_dapp_request(rest)?;
+                Self::UnshieldAndCall {
+                    unshield_info: Self::unpack_unshield_request(rest)?,
+                    dapp_request,
+                }
+            }
+            14 => {
+                let (inc_address, rest) = Self::unpack_bytes148(rest)?;
+                let (close_accounts, rest) = Self::unpack_bool(rest)?;
+                if !rest.is_empty() {
+                    return Err(BridgeError::TrailingInstructionData.into());
+                }
+                Self::WithdrawAll {
+                    inc_address: *inc_address,
+                    close_accounts,
+                }
+            }
+            15 => {
+                Self::SetQuorumThreshold {
+                    threshold_info: Self::unpack_beacon_instruction_request(rest)?,
+                }
+            }
+            16 => {
+                let (capacity, rest) = Self::unpack_u8(rest)?;
+                if !rest.is_empty() {
+                    return Err(BridgeError::TrailingInstructionData.into());
+                }
+                Self::ResizeProxy { capacity }
+            }
+            17 => Self::InitProofBuffer,
+            18 => {
+                let (offset, rest) = Self::unpack_u32(rest)?;
+                let (data_len, rest) = Self::unpack_u16(rest)?;
+                let (data, rest) = Self::unpack_nbytes(rest, data_len as usize)?;
+                if !rest.is_empty() {
+                    return Err(BridgeError::TrailingInstructionData.into());
+                }
+                Self::WriteProofChunk { offset, data: data.to_vec() }
+            }
+            19 => Self::UnshieldFromBuffer,
+            20 => {
+                let (inst_root, rest) = Self::unpack_bytes32(rest)?;
+                let (blk_data, rest) = Self::unpack_bytes32(rest)?;
+                let (indexes, rest) = Self::unpack_indexes(rest)?;
+                let (signatures, rest) = Self::unpack_signatures(rest)?;
+                Self::validate_proof(&[], &[], &indexes, &signatures, rest)?;
+                Self::VerifyBeaconBlock {
+                    block_info: BeaconBlockRequest {
+                        inst_root: *inst_root,
+                        blk_data: *blk_data,
+                        indexes,
+                        signatures,
+                    },
+                }
+            }
+            21 => {
+                let (inst, rest) = Self::unpack_bytes162(rest)?;
+                let (height, rest) = Self::unpack_u64(rest)?;
+                let (inst_paths, rest) = Self::unpack_inst_paths(rest)?;
+                let (inst_path_is_lefts, rest) = Self::unpack_inst_path_is_lefts(rest)?;
+                let (inst_root, rest) = Self::unpack_bytes32(rest)?;
+                Self::validate_proof(&inst_paths, &inst_path_is_lefts, &[], &[], rest)?;
+                Self::UnshieldVerified {
+                    unshield_info: VerifiedUnshieldRequest {
+                        inst: *inst,
+                        height,
+                        inst_paths,
+                        inst_path_is_lefts,
+                        inst_root: *inst_root,
+                    },
+                }
+            }
+            22 => {
+                let (inst, rest) = Self::unpack_bytes162(rest)?;
+                let (height, rest) = Self::unpack_u64(rest)?;
+                let (inst_paths, rest) = Self::unpack_inst_paths(rest)?;
+                let (inst_path_is_lefts, rest) = Self::unpack_inst_path_is_lefts(rest)?;
+                let (inst_root, rest) = Self::unpack_bytes32(rest)?;
+                let (blk_data, rest) = Self::unpack_bytes32(rest)?;
+                let (indexes, rest) = Self::unpack_indexes(rest)?;
+                Self::validate_proof(&inst_paths, &inst_path_is_lefts, &[], &[], rest)?;
+                Self::UnshieldSecp256k1 {
+                    unshield_info: Secp256k1UnshieldRequest {
+                        inst: *inst,
+                        height,
+                        inst_paths,
+                        inst_path_is_lefts,
+                        inst_root: *inst_root,
+                        blk_data: *blk_data,
+                        indexes,
+                    },
+                }
+            }
+            23 => Self::InitAllowlist,
+            25 => Self::MigrateProxy,
+            26 => Self::CloseProofBuffer,
             _ => return Err(InvalidInstruction.into()),
         })
     }
 
+    /// Unpacks a beacon instruction with u16 length followed by its height and proof.
+    fn unpack_beacon_instruction_request(input: &[u8]) -> Result<BeaconInstructionRequest, ProgramError> {
+        let (inst_len, rest) = Self::unpack_u16(input)?;
+        let (inst, rest) = Self::unpack_nbytes(rest, inst_len as usize)?;
+        let (height, rest) = Self::unpack_u64(rest)?;
+        let (inst_paths, rest) = Self::unpack_inst_paths(rest)?;
+        let (inst_path_is_lefts, rest) = Self::unpack_inst_path_is_lefts(rest)?;
+        let (inst_root, rest) = Self::unpack_bytes32(rest)?;
+        let (blk_data, rest) = Self::unpack_bytes32(rest)?;
+        let (indexes, rest) = Self::unpack_indexes(rest)?;
+        let (signatures, rest) = Self::unpack_signatures(rest)?;
+        Self::validate_proof(&inst_paths, &inst_path_is_lefts, &indexes, &signatures, rest)?;
+        Ok(BeaconInstructionRequest {
+            inst: inst.to_vec(),
+            height,
+            inst_paths,
+            inst_path_is_lefts,
+            inst_root: *inst_root,
+            blk_data: *blk_data,
+            indexes,
+            signatures,
+        })
+    }
+
+    /// Checks structural invariants of a beacon proof once it has been decoded.
+    fn validate_proof(
+        inst_paths: &[[u8; 32]],
+        inst_path_is_lefts: &[bool],
+        indexes: &[u8],
+        signatures: &[[u8; 65]],
+        rest: &[u8],
+    ) -> Result<(), ProgramError> {
+        if inst_paths.len() != inst_path_is_lefts.len() {
+            msg!("Length of inst paths and is lefts not match {}, {}", inst_paths.len(), inst_path_is_lefts.len());
+            return Err(BridgeError::InstPathLengthMismatch.into());
+        }
+        if indexes.len() != signatures.len() {
+            msg!("Length of indexes and signatures not match {}, {}", indexes.len(), signatures.len());
+            return Err(BridgeError::SignatureLengthMismatch.into());
+        }
+        if !rest.is_empty() {
+            msg!("{} trailing bytes in instruction data", rest.len());
+            return Err(BridgeError::TrailingInstructionData.into());
+        }
+        Ok(())
+    }
+
+    fn unpack_inst_paths(input: &[u8]) -> Result<(Vec<[u8; 32]>, &[u8]), ProgramError> {
+        let (inst_paths_len, mut rest) = Self::unpack_u8(input)?;
+        let mut inst_paths = Vec::with_capacity(inst_paths_len as usize + 1);
+        for _ in 0..inst_paths_len {
+            let (inst_node, rest_) = Self::unpack_bytes32(rest)?;
+            rest = rest_;
+            inst_paths.push(*inst_node);
+        }
+        Ok((inst_paths, rest))
+    }
+
+    fn unpack_inst_path_is_lefts(input: &[u8]) -> Result<(Vec<bool>, &[u8]), ProgramError> {
+        let (inst_paths_is_left_len, mut rest) = Self::unpack_u8(input)?;
+        let mut inst_path_is_lefts = Vec::with_capacity(inst_paths_is_left_len as usize + 1);
+        for _ in 0..inst_paths_is_left_len {
+            let (inst_paths_is_left, rest_) = Self::unpack_bool(rest)?;
+            rest = rest_;
+            inst_path_is_lefts.push(inst_paths_is_left);
+        }
+        Ok((inst_path_is_lefts, rest))
+    }
+
+    fn unpack_indexes(input: &[u8]) -> Result<(Vec<u8>, &[u8]), ProgramError> {
+        let (indexes_len, rest) = Self::unpack_u8(input)?;
+        let (indexes, rest) = Self::unpack_nbytes(rest, indexes_len as usize)?;
+        Ok((indexes.to_vec(), rest))
+    }
+
+    fn unpack_signatures(input: &[u8]) -> Result<(Vec<[u8; 65]>, &[u8]), ProgramError> {
+        let (signature_len, mut rest) = Self::unpack_u8(input)?;
+        let mut signatures = Vec::with_capacity(signature_len as usize + 1);
+        for _ in 0..signature_len {
+            let (signature, rest_) = Self::unpack_bytes65(rest)?;
+            rest = rest_;
+            signatures.push(*signature);
+        }
+        Ok((signatures, rest))
+    }
+
     fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
         if input.len() < 8 {
             msg!("u64 cannot be unpacked");
@@ -200,6 +657,34 @@ impl BridgeInstruction {
         Ok((value, rest))
     }
 
+    fn unpack_u16(input: &[u8]) -> Result<(u16, &[u8]), ProgramError> {
+        if input.len() < 2 {
+            msg!("u16 cannot be unpacked");
+            return Err(InstructionUnpackError.into());
+        }
+        let (bytes, rest) = input.split_at(2);
+        let value = bytes
+            .get(..2)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u16::from_le_bytes)
+            .ok_or(InstructionUnpackError)?;
+        Ok((value, rest))
+    }
+
+    fn unpack_u32(input: &[u8]) -> Result<(u32, &[u8]), ProgramError> {
+        if input.len() < 4 {
+            msg!("u32 cannot be unpacked");
+            return Err(InstructionUnpackError.into());
+        }
+        let (bytes, rest) = input.split_at(4);
+        let value = bytes
+            .get(..4)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u32::from_le_bytes)
+            .ok_or(InstructionUnpackError)?;
+        Ok((value, rest))
+    }
+
     fn unpack_bytes162(input: &[u8]) -> Result<(&[u8; 162], &[u8]), ProgramError> {
         if input.len() < 162 {
             msg!("162 bytes cannot be unpacked");
@@ -289,7 +774,7 @@ impl BridgeInstruction {
 
         match value {
             0 => Ok((false, rest)),
-            1 => Ok((false, rest)),
+            1 => Ok((true, rest)),
             _ => {
                 msg!("Boolean cannot be unpacked");
                 Err(BridgeError::InvalidBoolValue.into())
@@ -307,18 +792,115 @@ impl BridgeInstruction {
         Ok((pk, rest))
     }
 
-    fn unpack_nbytes(input: &[u8], n: u8) -> Result<(&[u8], &[u8]), ProgramError> {
-        if input.len() < n as usize {
-            msg!("Pubkey cannot be unpacked");
+    /// Unpacks an unshield proof encoded as in `UnShield` instruction data.
+    pub fn unpack_unshield_proof(input: &[u8]) -> Result<UnshieldRequest, ProgramError> {
+        Self::unpack_unshield_request(input)
+    }
+
+    fn unpack_unshield_request(input: &[u8]) -> Result<UnshieldRequest, ProgramError> {
+        let (inst, rest) =  Self::unpack_bytes162(input)?;
+        let (height, rest) = Self::unpack_u64(rest)?;
+        let (inst_paths, rest) = Self::unpack_inst_paths(rest)?;
+        let (inst_path_is_lefts, rest) = Self::unpack_inst_path_is_lefts(rest)?;
+        let (inst_root, rest) = Self::unpack_bytes32(rest)?;
+        let (blk_data, rest) = Self::unpack_bytes32(rest)?;
+        let (indexes, rest) = Self::unpack_indexes(rest)?;
+        let (signatures, rest) = Self::unpack_signatures(rest)?;
+        Self::validate_proof(&inst_paths, &inst_path_is_lefts, &indexes, &signatures, rest)?;
+
+        Ok(UnshieldRequest {
+            inst: *inst,
+            height,
+            inst_paths,
+            inst_path_is_lefts,
+            inst_root: *inst_root,
+            blk_data: *blk_data,
+            indexes,
+            signatures,
+        })
+    }
+
+    fn unpack_wide_dapp_request(input: &[u8]) -> Result<(DappRequest, &[u8]), ProgramError> {
+        let (inst_len, rest) = Self::unpack_u32(input)?;
+        let (inst_data, rest) = Self::unpack_nbytes(rest, inst_len as usize)?;
+        let (acc_len, rest) = Self::unpack_u16(rest)?;
+        let (sign_index, rest) = Self::unpack_u16(rest)?;
+        let (min_balances, rest) = Self::unpack_balance_assertions(rest)?;
+        let (max_spends, rest) = Self::unpack_balance_assertions(rest)?;
+        Ok((
+            DappRequest {
+                inst: inst_data.to_vec(),
+                num_acc: acc_len,
+                sign_index,
+                min_balances,
+                max_spends,
+            },
+            rest,
+        ))
+    }
+
+    fn unpack_balance_assertions(input: &[u8]) -> Result<(Vec<BalanceAssertion>, &[u8]), ProgramError> {
+        let (len, mut rest) = Self::unpack_u8(input)?;
+        let mut assertions = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            let (account_index, next) = Self::unpack_u16(rest)?;
+            let (amount, next) = Self::unpack_u64(next)?;
+            assertions.push(BalanceAssertion { account_index, amount });
+            rest = next;
+        }
+        Ok((assertions, rest))
+    }
+
+    /// Unpacks an account index or count, u16 in wide encodings and u8 otherwise.
+    fn unpack_index(input: &[u8], wide: bool) -> Result<(u16, &[u8]), ProgramError> {
+        if wide {
+            Self::unpack_u16(input)
+        } else {
+            Self::unpack_u8(input).map(|(value, rest)| (value.into(), rest))
+        }
+    }
+
+    fn unpack_dapp_call(input: &[u8], wide: bool) -> Result<(DappCall, &[u8]), ProgramError> {
+        let (program_index, rest) = Self::unpack_index(input, wide)?;
+        let (num_ranges, mut rest) = Self::unpack_u8(rest)?;
+        let mut account_ranges = Vec::with_capacity(num_ranges as usize);
+        for _ in 0..num_ranges {
+            let (start, next) = Self::unpack_index(rest, wide)?;
+            let (len, next) = Self::unpack_index(next, wide)?;
+            account_ranges.push((start, len));
+            rest = next;
+        }
+        let (inst_len, rest) = if wide {
+            Self::unpack_u32(rest)?
+        } else {
+            Self::unpack_u8(rest).map(|(value, rest)| (value.into(), rest))?
+        };
+        let (inst, rest) = Self::unpack_nbytes(rest, inst_len as usize)?;
+        Ok((
+            DappCall {
+                program_index,
+                account_ranges,
+                inst: inst.to_vec(),
+            },
+            rest,
+        ))
+    }
+
+    fn unpack_nbytes(input: &[u8], n: usize) -> Result<(&[u8], &[u8]), ProgramError> {
+        if input.len() < n {
+            msg!("{} bytes cannot be unpacked", n);
             return Err(InstructionUnpackError.into());
         }
-        let (key, rest) = input.split_at(n as usize);
+        let (key, rest) = input.split_at(n);
         Ok((key, rest))
     }
 
+    /// Packs a [BridgeInstruction](enum.BridgeInstruction.html) into a byte buffer.
+    ///
+    /// The output is always accepted by `unpack` and decodes back to the same value.
     pub fn pack(&self) -> Vec<u8> {
         let mut buf = Vec::with_capacity(size_of::<Self>());
-        match *self {
+        match self {
             Self::Shield {
                 amount,
                 inc_address,
@@ -327,13 +909,273 @@ impl BridgeInstruction {
                 buf.extend_from_slice(&amount.to_le_bytes());
                 buf.extend_from_slice(inc_address.as_ref());
             }
-            // todo: implement unshield and init bridge
-            _ => {
-
+            Self::UnShield { unshield_info } => {
+                buf.push(1);
+                Self::pack_unshield_request(&mut buf, unshield_info);
+            }
+            Self::InitBeacon { init_beacon_info } => {
+                buf.push(24);
+                buf.extend_from_slice(init_beacon_info.vault.as_ref());
+                buf.push(init_beacon_info.bump_seed);
+                Self::pack_len(&mut buf, init_beacon_info.beacons.len());
+                for beacon in &init_beacon_info.beacons {
+                    buf.extend_from_slice(&beacon.to_bytes());
+                }
+                buf.push(init_beacon_info.quorum_threshold.numerator);
+                buf.push(init_beacon_info.quorum_threshold.denominator);
+            }
+            Self::DappInteraction { dapp_request } => {
+                let narrow = (
+                    u8::try_from(dapp_request.inst.len()),
+                    u8::try_from(dapp_request.num_acc),
+                    u8::try_from(dapp_request.sign_index),
+                    dapp_request.min_balances.is_empty() && dapp_request.max_spends.is_empty(),
+                );
+                // keep the legacy encoding whenever it fits
+                if let (Ok(inst_len), Ok(num_acc), Ok(sign_index), true) = narrow {
+                    buf.push(3);
+                    buf.push(inst_len);
+                    buf.extend_from_slice(&dapp_request.inst);
+                    buf.push(num_acc);
+                    buf.push(sign_index);
+                } else {
+                    buf.push(12);
+                    Self::pack_wide_dapp_request(&mut buf, dapp_request);
+                }
+            }
+            Self::WithdrawRequest {
+                amount,
+                inc_address,
+            } => {
+                buf.push(4);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(inc_address.as_ref());
+            }
+            Self::SwapBeaconCommittee { swap_info } => {
+                buf.push(5);
+                Self::pack_beacon_instruction_request(&mut buf, swap_info);
+            }
+            Self::ShieldNative {
+                amount,
+                inc_address,
+            } => {
+                buf.push(6);
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(inc_address.as_ref());
+            }
+            Self::Pause => buf.push(7),
+            Self::Unpause => buf.push(8),
+            Self::AddAllowedProgram { program } => {
+                buf.push(9);
+                buf.extend_from_slice(program.as_ref());
+            }
+            Self::RemoveAllowedProgram { program } => {
+                buf.push(10);
+                buf.extend_from_slice(program.as_ref());
             }
+            Self::BatchDappInteraction { batch_request } => {
+                let fits_u8 = |value: usize| value <= u8::MAX as usize;
+                // keep the legacy encoding whenever it fits
+                let narrow = fits_u8(batch_request.num_acc.into())
+                    && fits_u8(batch_request.sign_index.into())
+                    && batch_request.calls.iter().all(|call| {
+                        fits_u8(call.program_index.into())
+                            && fits_u8(call.inst.len())
+                            && call
+                                .account_ranges
+                                .iter()
+                                .all(|(start, len)| fits_u8((*start).into()) && fits_u8((*len).into()))
+                    });
+                let pack_index = |buf: &mut Vec<u8>, index: u16| {
+                    if narrow {
+                        buf.push(index as u8);
+                    } else {
+                        buf.extend_from_slice(&index.to_le_bytes());
+                    }
+                };
+                buf.push(if narrow { 11 } else { 27 });
+                pack_index(&mut buf, batch_request.num_acc);
+                pack_index(&mut buf, batch_request.sign_index);
+                Self::pack_len(&mut buf, batch_request.calls.len());
+                for call in &batch_request.calls {
+                    pack_index(&mut buf, call.program_index);
+                    Self::pack_len(&mut buf, call.account_ranges.len());
+                    for (start, len) in &call.account_ranges {
+                        pack_index(&mut buf, *start);
+                        pack_index(&mut buf, *len);
+                    }
+                    if narrow {
+                        buf.push(call.inst.len() as u8);
+                    } else {
+                        let inst_len = u32::try_from(call.inst.len()).expect("instruction length exceeds u32::MAX");
+                        buf.extend_from_slice(&inst_len.to_le_bytes());
+                    }
+                    buf.extend_from_slice(&call.inst);
+                }
+            }
+            Self::UnshieldAndCall { unshield_info, dapp_request } => {
+                buf.push(13);
+                Self::pack_wide_dapp_request(&mut buf, dapp_request);
+                Self::pack_unshield_request(&mut buf, unshield_info);
+            }
+            Self::WithdrawAll { inc_address, close_accounts } => {
+                buf.push(14);
+                buf.extend_from_slice(inc_address);
+                buf.push(*close_accounts as u8);
+            }
+            Self::SetQuorumThreshold { threshold_info } => {
+                buf.push(15);
+                Self::pack_beacon_instruction_request(&mut buf, threshold_info);
+            }
+            Self::ResizeProxy { capacity } => {
+                buf.push(16);
+                buf.push(*capacity);
+            }
+            Self::InitProofBuffer => buf.push(17),
+            Self::WriteProofChunk { offset, data } => {
+                buf.push(18);
+                buf.extend_from_slice(&offset.to_le_bytes());
+                buf.extend_from_slice(
+                    &u16::try_from(data.len()).expect("chunk length exceeds u16::MAX").to_le_bytes()
+                );
+                buf.extend_from_slice(data);
+            }
+            Self::UnshieldFromBuffer => buf.push(19),
+            Self::VerifyBeaconBlock { block_info } => {
+                buf.push(20);
+                buf.extend_from_slice(&block_info.inst_root);
+                buf.extend_from_slice(&block_info.blk_data);
+                Self::pack_len(&mut buf, block_info.indexes.len());
+                buf.extend_from_slice(&block_info.indexes);
+                Self::pack_len(&mut buf, block_info.signatures.len());
+                for signature in &block_info.signatures {
+                    buf.extend_from_slice(signature);
+                }
+            }
+            Self::UnshieldVerified { unshield_info } => {
+                buf.push(21);
+                buf.extend_from_slice(&unshield_info.inst);
+                buf.extend_from_slice(&unshield_info.height.to_le_bytes());
+                Self::pack_len(&mut buf, unshield_info.inst_paths.len());
+                for inst_node in &unshield_info.inst_paths {
+                    buf.extend_from_slice(inst_node);
+                }
+                Self::pack_len(&mut buf, unshield_info.inst_path_is_lefts.len());
+                for is_left in &unshield_info.inst_path_is_lefts {
+                    buf.push(*is_left as u8);
+                }
+                buf.extend_from_slice(&unshield_info.inst_root);
+            }
+            Self::UnshieldSecp256k1 { unshield_info } => {
+                buf.push(22);
+                buf.extend_from_slice(&unshield_info.inst);
+                buf.extend_from_slice(&unshield_info.height.to_le_bytes());
+                Self::pack_len(&mut buf, unshield_info.inst_paths.len());
+                for inst_node in &unshield_info.inst_paths {
+                    buf.extend_from_slice(inst_node);
+                }
+                Self::pack_len(&mut buf, unshield_info.inst_path_is_lefts.len());
+                for is_left in &unshield_info.inst_path_is_lefts {
+                    buf.push(*is_left as u8);
+                }
+                buf.extend_from_slice(&unshield_info.inst_root);
+                buf.extend_from_slice(&unshield_info.blk_data);
+                Self::pack_len(&mut buf, unshield_info.indexes.len());
+                buf.extend_from_slice(&unshield_info.indexes);
+            }
+            Self::InitAllowlist => buf.push(23),
+            Self::MigrateProxy => buf.push(25),
+            Self::CloseProofBuffer => buf.push(26),
         }
         buf
     }
+
+    /// Packs an unshield proof as in `UnShield` instruction data, to be uploaded to a proof buffer.
+    pub fn pack_unshield_proof(unshield_info: &UnshieldRequest) -> Vec<u8> {
+        let mut buf = Vec::new();
+        Self::pack_unshield_request(&mut buf, unshield_info);
+        buf
+    }
+
+    /// Packs a beacon instruction with u16 length followed by its height and proof.
+    fn pack_beacon_instruction_request(buf: &mut Vec<u8>, swap_info: &BeaconInstructionRequest) {
+        buf.extend_from_slice(
+            &u16::try_from(swap_info.inst.len()).expect("instruction length exceeds u16::MAX").to_le_bytes()
+        );
+        buf.extend_from_slice(&swap_info.inst);
+        buf.extend_from_slice(&swap_info.height.to_le_bytes());
+        Self::pack_proof(
+            buf,
+            &swap_info.inst_paths,
+            &swap_info.inst_path_is_lefts,
+            &swap_info.inst_root,
+            &swap_info.blk_data,
+            &swap_info.indexes,
+            &swap_info.signatures,
+        );
+    }
+
+    /// Packs the beacon proof fields which follow the instruction and height.
+    fn pack_unshield_request(buf: &mut Vec<u8>, unshield_info: &UnshieldRequest) {
+        buf.extend_from_slice(&unshield_info.inst);
+        buf.extend_from_slice(&unshield_info.height.to_le_bytes());
+        Self::pack_proof(
+            buf,
+            &unshield_info.inst_paths,
+            &unshield_info.inst_path_is_lefts,
+            &unshield_info.inst_root,
+            &unshield_info.blk_data,
+            &unshield_info.indexes,
+            &unshield_info.signatures,
+        );
+    }
+
+    fn pack_wide_dapp_request(buf: &mut Vec<u8>, dapp_request: &DappRequest) {
+        let inst_len = u32::try_from(dapp_request.inst.len()).expect("instruction length exceeds u32::MAX");
+        buf.extend_from_slice(&inst_len.to_le_bytes());
+        buf.extend_from_slice(&dapp_request.inst);
+        buf.extend_from_slice(&dapp_request.num_acc.to_le_bytes());
+        buf.extend_from_slice(&dapp_request.sign_index.to_le_bytes());
+        for assertions in [&dapp_request.min_balances, &dapp_request.max_spends] {
+            Self::pack_len(buf, assertions.len());
+            for assertion in assertions {
+                buf.extend_from_slice(&assertion.account_index.to_le_bytes());
+                buf.extend_from_slice(&assertion.amount.to_le_bytes());
+            }
+        }
+    }
+
+    fn pack_proof(
+        buf: &mut Vec<u8>,
+        inst_paths: &[[u8; 32]],
+        inst_path_is_lefts: &[bool],
+        inst_root: &[u8; 32],
+        blk_data: &[u8; 32],
+        indexes: &[u8],
+        signatures: &[[u8; 65]],
+    ) {
+        Self::pack_len(buf, inst_paths.len());
+        for inst_node in inst_paths {
+            buf.extend_from_slice(inst_node);
+        }
+        Self::pack_len(buf, inst_path_is_lefts.len());
+        for is_left in inst_path_is_lefts {
+            buf.push(*is_left as u8);
+        }
+        buf.extend_from_slice(inst_root);
+        buf.extend_from_slice(blk_data);
+        Self::pack_len(buf, indexes.len());
+        buf.extend_from_slice(indexes);
+        Self::pack_len(buf, signatures.len());
+        for signature in signatures {
+            buf.extend_from_slice(signature);
+        }
+    }
+
+    /// Lists are prefixed with a single length byte, longer lists can not be encoded.
+    fn pack_len(buf: &mut Vec<u8>, len: usize) {
+        buf.push(u8::try_from(len).expect("list length exceeds u8::MAX"));
+    }
 }
 
 /// Creates a 'Shield' instruction.
@@ -358,4 +1200,759 @@ pub fn shield(
         ],
         data: BridgeInstruction::Shield { amount, inc_address: inc_address.clone() }.pack(),
     }
-}
\ No newline at end of file
+}
+
+/// Creates a 'ShieldNative' instruction.
+pub fn shield_native(
+    program_id: Pubkey,
+    amount: u64,
+    shield_maker: Pubkey,
+    incognito_proxy: Pubkey,
+    bump_seed: u8,
+    inc_address: &[u8; 148],
+) -> Instruction {
+    let vault_authority = get_vault_authority_address(&program_id, &incognito_proxy, bump_seed);
+    let vault_token_account = get_associated_token_address(&vault_authority, &spl_token::native_mint::id());
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(shield_maker, true),
+            AccountMeta::new(vault_token_account, false),
+            AccountMeta::new_readonly(incognito_proxy, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: BridgeInstruction::ShieldNative { amount, inc_address: *inc_address }.pack(),
+    }
+}
+
+/// Creates an 'UnShield' instruction.
+///
+/// `unshield_token_account` is the associated token account of the unshield maker for meta type 157,
+/// a temporary wSOL account owned by the vault authority when unshielding SOL,
+/// or the associated token account of the unshield maker's signer authority for meta type 158.
+#[allow(clippy::too_many_arguments)]
+pub fn unshield(
+    program_id: Pubkey,
+    unshield_info: UnshieldRequest,
+    unshield_maker: Pubkey,
+    unshield_token_account: Pubkey,
+    vault_account: Pubkey,
+    incognito_proxy: Pubkey,
+    bump_seed: u8,
+    token_mint: Pubkey,
+    payer: Pubkey,
+) -> Instruction {
+    let vault_authority = get_vault_authority_address(&program_id, &incognito_proxy, bump_seed);
+    let vault_token_account = get_associated_token_address(&vault_authority, &token_mint);
+    let tx_id = array_ref![unshield_info.inst, 98, 32];
+    let (burn_tx, _) = find_burn_tx_address(&program_id, &incognito_proxy, tx_id);
+    let receiver_owner = if unshield_info.inst[0] == UNSHIELD_TO_PDA_META_TYPE {
+        find_signer_authority_address(&program_id, &unshield_maker).0
+    } else {
+        unshield_maker
+    };
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(vault_token_account, false),
+            AccountMeta::new_readonly(unshield_maker, false),
+            AccountMeta::new_readonly(vault_authority, false),
+            AccountMeta::new_readonly(vault_account, false),
+            AccountMeta::new_readonly(incognito_proxy, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(unshield_token_account, false),
+            AccountMeta::new(burn_tx, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(receiver_owner, false),
+            AccountMeta::new_readonly(token_mint, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: BridgeInstruction::UnShield { unshield_info }.pack(),
+    }
+}
+
+/// Creates an 'UnshieldAndCall' instruction, the unshield maker signs the dapp call.
+///
+/// Tokens are unshielded into the associated token account of the signer authority derived from
+/// `unshield_maker`, `accounts` are the accounts expected by `program_dest`.
+#[allow(clippy::too_many_arguments)]
+pub fn unshield_and_call(
+    program_id: Pubkey,
+    unshield_info: UnshieldRequest,
+    unshield_maker: Pubkey,
+    vault_account: Pubkey,
+    incognito_proxy: Pubkey,
+    bump_seed: u8,
+    token_mint: Pubkey,
+    payer: Pubkey,
+    program_dest: Pubkey,
+    accounts: Vec<AccountMeta>,
+    inst: Vec<u8>,
+) -> Instruction {
+    let (signer_authority, _) = find_signer_authority_address(&program_id, &unshield_maker);
+    let unshield_token_account = get_associated_token_address(&signer_authority, &token_mint);
+    let mut accounts_invoke = unshield(
+        program_id,
+        unshield_info.clone(),
+        unshield_maker,
+        unshield_token_account,
+        vault_account,
+        incognito_proxy,
+        bump_seed,
+        token_mint,
+        payer,
+    )
+    .accounts;
+    accounts_invoke[1].is_signer = true;
+    let (dapp_accounts, num_acc, sign_index) = dapp_accounts(&program_id, unshield_maker, accounts);
+    accounts_invoke.extend(dapp_accounts);
+    accounts_invoke.push(AccountMeta::new_readonly(program_dest, false));
+    accounts_invoke.push(AccountMeta::new_readonly(incognito_proxy, false));
+    accounts_invoke.push(AccountMeta::new_readonly(find_allowlist_address(&program_id).0, false));
+
+    Instruction {
+        program_id,
+        accounts: accounts_invoke,
+        data: BridgeInstruction::UnshieldAndCall {
+            unshield_info,
+            dapp_request: DappRequest {
+                inst,
+                num_acc,
+                sign_index,
+                ..DappRequest::default()
+            },
+        }
+        .pack(),
+    }
+}
+
+/// Creates an 'InitBeacon' instruction.
+///
+/// `incognito_proxy` must be created with at least `IncognitoProxy::space(beacons.len())` bytes,
+/// other than `IncognitoProxy::LEGACY_LEN` which is the size of unversioned accounts.
+pub fn init_beacon(
+    program_id: Pubkey,
+    incognito_proxy: Pubkey,
+    vault_account: Pubkey,
+    guardian: Pubkey,
+    beacons: Vec<Secp256k1Pubkey>,
+    quorum_threshold: QuorumThreshold,
+) -> Instruction {
+    let (_, bump_seed) = Pubkey::find_program_address(&[incognito_proxy.as_ref()], &program_id);
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new(incognito_proxy, false),
+            AccountMeta::new(vault_account, false),
+            AccountMeta::new_readonly(guardian, true),
+        ],
+        data: BridgeInstruction::InitBeacon {
+            init_beacon_info: InitBeaconRequest {
+                vault: vault_account,
+                bump_seed,
+                quorum_threshold,
+                beacons,
+            },
+        }
+        .pack(),
+    }
+}
+
+/// Creates a 'DappInteraction' instruction.
+///
+/// `accounts` are the accounts expected by `program_dest`. The signer authority derived from
+/// `signer` signs the inner instruction through the bridge, so it is never required as an outer signer.
+pub fn dapp_interaction(
+    program_id: Pubkey,
+    signer: Pubkey,
+    incognito_proxy: Pubkey,
+    program_dest: Pubkey,
+    accounts: Vec<AccountMeta>,
+    inst: Vec<u8>,
+) -> Instruction {
+    dapp_interaction_with_assertions(program_id, signer, incognito_proxy, program_dest, accounts, inst, vec![], vec![])
+}
+
+/// Creates a 'DappInteraction' instruction that fails unless each token account in `min_balances`
+/// holds at least `amount` after the call and each one in `max_spends` loses at most `amount`.
+/// Token accounts are referred to by their `account_index` in `accounts`.
+#[allow(clippy::too_many_arguments)]
+pub fn dapp_interaction_with_assertions(
+    program_id: Pubkey,
+    signer: Pubkey,
+    incognito_proxy: Pubkey,
+    program_dest: Pubkey,
+    accounts: Vec<AccountMeta>,
+    inst: Vec<u8>,
+    min_balances: Vec<BalanceAssertion>,
+    max_spends: Vec<BalanceAssertion>,
+) -> Instruction {
+    let (mut accounts_invoke, num_acc, sign_index) = dapp_accounts(&program_id, signer, accounts);
+    accounts_invoke.push(AccountMeta::new_readonly(program_dest, false));
+    accounts_invoke.push(AccountMeta::new_readonly(incognito_proxy, false));
+    accounts_invoke.push(AccountMeta::new_readonly(find_allowlist_address(&program_id).0, false));
+
+    Instruction {
+        program_id,
+        accounts: accounts_invoke,
+        data: BridgeInstruction::DappInteraction {
+            dapp_request: DappRequest {
+                inst,
+                num_acc,
+                sign_index,
+                min_balances,
+                max_spends,
+            },
+        }
+        .pack(),
+    }
+}
+
+/// Creates a 'BatchDappInteraction' instruction, `accounts` includes the programs called.
+pub fn batch_dapp_interaction(
+    program_id: Pubkey,
+    signer: Pubkey,
+    incognito_proxy: Pubkey,
+    accounts: Vec<AccountMeta>,
+    calls: Vec<DappCall>,
+) -> Instruction {
+    let (mut accounts_invoke, num_acc, sign_index) = dapp_accounts(&program_id, signer, accounts);
+    accounts_invoke.push(AccountMeta::new_readonly(incognito_proxy, false));
+    accounts_invoke.push(AccountMeta::new_readonly(find_allowlist_address(&program_id).0, false));
+
+    Instruction {
+        program_id,
+        accounts: accounts_invoke,
+        data: BridgeInstruction::BatchDappInteraction {
+            batch_request: BatchDappRequest {
+                calls,
+                num_acc,
+                sign_index,
+            },
+        }
+        .pack(),
+    }
+}
+
+// signer followed by the forwarded accounts, the signer authority is signed by the bridge
+fn dapp_accounts(program_id: &Pubkey, signer: Pubkey, accounts: Vec<AccountMeta>) -> (Vec<AccountMeta>, u16, u16) {
+    let (signer_authority, _) = find_signer_authority_address(program_id, &signer);
+    let num_acc = u16::try_from(accounts.len()).expect("too many accounts");
+    // an out of range index means the signer authority is not needed by the callee
+    let sign_index = accounts
+        .iter()
+        .position(|account| account.pubkey == signer_authority)
+        .map_or(num_acc, |index| index as u16);
+
+    let mut accounts_invoke = Vec::with_capacity(accounts.len() + 4);
+    accounts_invoke.push(AccountMeta::new_readonly(signer, true));
+    for account in accounts {
+        let is_signer = account.is_signer && account.pubkey != signer_authority;
+        accounts_invoke.push(AccountMeta { is_signer, ..account });
+    }
+    (accounts_invoke, num_acc, sign_index)
+}
+
+/// Creates a 'WithdrawRequest' instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_request(
+    program_id: Pubkey,
+    amount: u64,
+    signer: Pubkey,
+    incognito_proxy: Pubkey,
+    bump_seed: u8,
+    token_mint: Pubkey,
+    inc_address: &[u8; 148],
+) -> Instruction {
+    let (signer_authority, _) = find_signer_authority_address(&program_id, &signer);
+    let signer_token_account = get_associated_token_address(&signer_authority, &token_mint);
+    let vault_authority = get_vault_authority_address(&program_id, &incognito_proxy, bump_seed);
+    let vault_token_account = get_associated_token_address(&vault_authority, &token_mint);
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(signer_token_account, false),
+            AccountMeta::new(vault_token_account, false),
+            AccountMeta::new_readonly(incognito_proxy, false),
+            AccountMeta::new_readonly(signer, true),
+            AccountMeta::new_readonly(signer_authority, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: BridgeInstruction::WithdrawRequest { amount, inc_address: *inc_address }.pack(),
+    }
+}
+
+/// Creates a 'WithdrawAll' instruction sweeping the signer authority associated token account of each mint.
+pub fn withdraw_all(
+    program_id: Pubkey,
+    signer: Pubkey,
+    incognito_proxy: Pubkey,
+    bump_seed: u8,
+    token_mints: &[Pubkey],
+    inc_address: &[u8; 148],
+    close_accounts: bool,
+) -> Instruction {
+    let (signer_authority, _) = find_signer_authority_address(&program_id, &signer);
+    let vault_authority = get_vault_authority_address(&program_id, &incognito_proxy, bump_seed);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(incognito_proxy, false),
+        if close_accounts { AccountMeta::new(signer, true) } else { AccountMeta::new_readonly(signer, true) },
+        AccountMeta::new_readonly(signer_authority, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    for token_mint in token_mints {
+        accounts.push(AccountMeta::new(get_associated_token_address(&signer_authority, token_mint), false));
+        accounts.push(AccountMeta::new(get_associated_token_address(&vault_authority, token_mint), false));
+    }
+    Instruction {
+        program_id,
+        accounts,
+        data: BridgeInstruction::WithdrawAll { inc_address: *inc_address, close_accounts }.pack(),
+    }
+}
+
+/// Creates a 'SwapBeaconCommittee' instruction.
+pub fn swap_beacon_committee(
+    program_id: Pubkey,
+    incognito_proxy: Pubkey,
+    swap_info: BeaconInstructionRequest,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(incognito_proxy, false),
+        ],
+        data: BridgeInstruction::SwapBeaconCommittee { swap_info }.pack(),
+    }
+}
+
+/// Creates a 'SetQuorumThreshold' instruction.
+pub fn set_quorum_threshold(
+    program_id: Pubkey,
+    incognito_proxy: Pubkey,
+    threshold_info: BeaconInstructionRequest,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(incognito_proxy, false),
+        ],
+        data: BridgeInstruction::SetQuorumThreshold { threshold_info }.pack(),
+    }
+}
+
+/// Creates a 'ResizeProxy' instruction.
+pub fn resize_proxy(
+    program_id: Pubkey,
+    incognito_proxy: Pubkey,
+    guardian: Pubkey,
+    payer: Pubkey,
+    capacity: u8,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(incognito_proxy, false),
+            AccountMeta::new_readonly(guardian, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: BridgeInstruction::ResizeProxy { capacity }.pack(),
+    }
+}
+
+/// Creates a 'MigrateProxy' instruction.
+pub fn migrate_proxy(
+    program_id: Pubkey,
+    incognito_proxy: Pubkey,
+    guardian: Pubkey,
+    upgrade_authority: Pubkey,
+    payer: Pubkey,
+) -> Instruction {
+    let (program_data, _) = Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new(incognito_proxy, false),
+            AccountMeta::new_readonly(guardian, true),
+            AccountMeta::new_readonly(upgrade_authority, true),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: BridgeInstruction::MigrateProxy.pack(),
+    }
+}
+
+/// Creates a 'VerifyBeaconBlock' instruction.
+pub fn verify_beacon_block(
+    program_id: Pubkey,
+    incognito_proxy: Pubkey,
+    payer: Pubkey,
+    block_info: BeaconBlockRequest,
+) -> Instruction {
+    let (verified_root, _) = find_verified_root_address(&program_id, &incognito_proxy, &block_info.inst_root);
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(incognito_proxy, false),
+            AccountMeta::new(verified_root, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: BridgeInstruction::VerifyBeaconBlock { block_info }.pack(),
+    }
+}
+
+/// Creates an 'UnshieldVerified' instruction, accounts are derived as for `unshield`.
+#[allow(clippy::too_many_arguments)]
+pub fn unshield_verified(
+    program_id: Pubkey,
+    unshield_info: VerifiedUnshieldRequest,
+    unshield_maker: Pubkey,
+    unshield_token_account: Pubkey,
+    vault_account: Pubkey,
+    incognito_proxy: Pubkey,
+    bump_seed: u8,
+    token_mint: Pubkey,
+    payer: Pubkey,
+) -> Instruction {
+    let (verified_root, _) = find_verified_root_address(&program_id, &incognito_proxy, &unshield_info.inst_root);
+    let mut accounts = unshield(
+        program_id,
+        UnshieldRequest {
+            inst: unshield_info.inst,
+            height: unshield_info.height,
+            inst_paths: Vec::new(),
+            inst_path_is_lefts: Vec::new(),
+            inst_root: [0; 32],
+            blk_data: [0; 32],
+            indexes: Vec::new(),
+            signatures: Vec::new(),
+        },
+        unshield_maker,
+        unshield_token_account,
+        vault_account,
+        incognito_proxy,
+        bump_seed,
+        token_mint,
+        payer,
+    )
+    .accounts;
+    accounts.push(AccountMeta::new_readonly(verified_root, false));
+    Instruction {
+        program_id,
+        accounts,
+        data: BridgeInstruction::UnshieldVerified { unshield_info }.pack(),
+    }
+}
+
+/// Creates a Secp256k1 program instruction checking the beacon `signatures` of a block,
+/// to be placed at `instruction_index` in the transaction.
+///
+/// The block message is stored once, followed by the eth address and signature of each beacon.
+pub fn secp256k1_beacon_signatures(
+    instruction_index: u8,
+    beacons: &[Secp256k1Pubkey],
+    blk_data: &[u8; 32],
+    inst_root: &[u8; 32],
+    indexes: &[u8],
+    signatures: &[[u8; 65]],
+) -> Instruction {
+    let encode_offset = |offset: usize| u16::try_from(offset).expect("secp256k1 instruction exceeds u16::MAX").to_le_bytes();
+    let message_offset = 1 + signatures.len() * SECP256K1_SIGNATURE_OFFSETS_LEN;
+    let mut offsets = vec![u8::try_from(signatures.len()).expect("signatures exceed u8::MAX")];
+    let mut payload = beacon_block_message(blk_data, inst_root).to_vec();
+    for (index_beacon, signature) in indexes.iter().zip(signatures) {
+        let eth_address_offset = message_offset + payload.len();
+        payload.extend_from_slice(&beacon_eth_address(&beacons[*index_beacon as usize]));
+        let signature_offset = message_offset + payload.len();
+        payload.extend_from_slice(signature);
+        offsets.extend_from_slice(&encode_offset(signature_offset));
+        offsets.push(instruction_index);
+        offsets.extend_from_slice(&encode_offset(eth_address_offset));
+        offsets.push(instruction_index);
+        offsets.extend_from_slice(&encode_offset(message_offset));
+        offsets.extend_from_slice(&32u16.to_le_bytes());
+        offsets.push(instruction_index);
+    }
+    offsets.extend_from_slice(&payload);
+    Instruction {
+        program_id: secp256k1_program::id(),
+        accounts: vec![],
+        data: offsets,
+    }
+}
+
+/// Creates the Secp256k1 program instruction at `secp256k1_instruction_index` and the following
+/// 'UnshieldSecp256k1' instruction, accounts are derived as for `unshield`.
+///
+/// `beacons` is the committee stored in the incognito proxy.
+#[allow(clippy::too_many_arguments)]
+pub fn unshield_secp256k1(
+    program_id: Pubkey,
+    unshield_info: UnshieldRequest,
+    beacons: &[Secp256k1Pubkey],
+    secp256k1_instruction_index: u8,
+    unshield_maker: Pubkey,
+    unshield_token_account: Pubkey,
+    vault_account: Pubkey,
+    incognito_proxy: Pubkey,
+    bump_seed: u8,
+    token_mint: Pubkey,
+    payer: Pubkey,
+) -> Vec<Instruction> {
+    let secp256k1_instruction = secp256k1_beacon_signatures(
+        secp256k1_instruction_index,
+        beacons,
+        &unshield_info.blk_data,
+        &unshield_info.inst_root,
+        &unshield_info.indexes,
+        &unshield_info.signatures,
+    );
+    let UnshieldRequest { inst, height, inst_paths, inst_path_is_lefts, inst_root, blk_data, indexes, .. } =
+        unshield_info.clone();
+    let mut accounts = unshield(
+        program_id,
+        unshield_info,
+        unshield_maker,
+        unshield_token_account,
+        vault_account,
+        incognito_proxy,
+        bump_seed,
+        token_mint,
+        payer,
+    )
+    .accounts;
+    accounts.push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+    vec![
+        secp256k1_instruction,
+        Instruction {
+            program_id,
+            accounts,
+            data: BridgeInstruction::UnshieldSecp256k1 {
+                unshield_info: Secp256k1UnshieldRequest {
+                    inst,
+                    height,
+                    inst_paths,
+                    inst_path_is_lefts,
+                    inst_root,
+                    blk_data,
+                    indexes,
+                },
+            }
+            .pack(),
+        },
+    ]
+}
+
+/// Creates an 'InitProofBuffer' instruction, `proof_buffer` must be created first in the same transaction.
+pub fn init_proof_buffer(program_id: Pubkey, proof_buffer: Pubkey, authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new(proof_buffer, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data: BridgeInstruction::InitProofBuffer.pack(),
+    }
+}
+
+/// Creates 'WriteProofChunk' instructions uploading `unshield_info` in chunks of at most `chunk_len` bytes.
+pub fn write_proof_chunks(
+    program_id: Pubkey,
+    proof_buffer: Pubkey,
+    authority: Pubkey,
+    unshield_info: &UnshieldRequest,
+    chunk_len: usize,
+) -> Vec<Instruction> {
+    BridgeInstruction::pack_unshield_proof(unshield_info)
+        .chunks(chunk_len)
+        .enumerate()
+        .map(|(i, chunk)| Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(proof_buffer, false),
+                AccountMeta::new_readonly(authority, true),
+            ],
+            data: BridgeInstruction::WriteProofChunk {
+                offset: u32::try_from(i * chunk_len).expect("proof length exceeds u32::MAX"),
+                data: chunk.to_vec(),
+            }
+            .pack(),
+        })
+        .collect()
+}
+
+/// Creates an 'UnshieldFromBuffer' instruction, accounts are derived as for `unshield`
+/// from `unshield_info` which must match the uploaded proof.
+#[allow(clippy::too_many_arguments)]
+pub fn unshield_from_buffer(
+    program_id: Pubkey,
+    proof_buffer: Pubkey,
+    authority: Pubkey,
+    unshield_info: UnshieldRequest,
+    unshield_maker: Pubkey,
+    unshield_token_account: Pubkey,
+    vault_account: Pubkey,
+    incognito_proxy: Pubkey,
+    bump_seed: u8,
+    token_mint: Pubkey,
+    payer: Pubkey,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(proof_buffer, false),
+        AccountMeta::new(authority, true),
+    ];
+    accounts.extend(
+        unshield(
+            program_id,
+            unshield_info,
+            unshield_maker,
+            unshield_token_account,
+            vault_account,
+            incognito_proxy,
+            bump_seed,
+            token_mint,
+            payer,
+        )
+        .accounts,
+    );
+    Instruction {
+        program_id,
+        accounts,
+        data: BridgeInstruction::UnshieldFromBuffer.pack(),
+    }
+}
+
+/// Creates a 'CloseProofBuffer' instruction.
+pub fn close_proof_buffer(program_id: Pubkey, proof_buffer: Pubkey, authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(proof_buffer, false),
+            AccountMeta::new(authority, true),
+        ],
+        data: BridgeInstruction::CloseProofBuffer.pack(),
+    }
+}
+
+/// Creates a 'Pause' instruction.
+pub fn pause(program_id: Pubkey, incognito_proxy: Pubkey, guardian: Pubkey) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(incognito_proxy, false),
+            AccountMeta::new_readonly(guardian, true),
+        ],
+        data: BridgeInstruction::Pause.pack(),
+    }
+}
+
+/// Creates an 'Unpause' instruction.
+pub fn unpause(program_id: Pubkey, incognito_proxy: Pubkey, guardian: Pubkey) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(incognito_proxy, false),
+            AccountMeta::new_readonly(guardian, true),
+        ],
+        data: BridgeInstruction::Unpause.pack(),
+    }
+}
+
+/// Creates an 'InitAllowlist' instruction.
+pub fn init_allowlist(
+    program_id: Pubkey,
+    incognito_proxy: Pubkey,
+    upgrade_authority: Pubkey,
+    payer: Pubkey,
+) -> Instruction {
+    let (allowlist, _) = find_allowlist_address(&program_id);
+    let (program_data, _) = Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(allowlist, false),
+            AccountMeta::new_readonly(incognito_proxy, false),
+            AccountMeta::new_readonly(upgrade_authority, true),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: BridgeInstruction::InitAllowlist.pack(),
+    }
+}
+
+/// Creates an 'AddAllowedProgram' instruction.
+pub fn add_allowed_program(
+    program_id: Pubkey,
+    incognito_proxy: Pubkey,
+    guardian: Pubkey,
+    program: Pubkey,
+) -> Instruction {
+    let (allowlist, _) = find_allowlist_address(&program_id);
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(allowlist, false),
+            AccountMeta::new_readonly(incognito_proxy, false),
+            AccountMeta::new_readonly(guardian, true),
+        ],
+        data: BridgeInstruction::AddAllowedProgram { program }.pack(),
+    }
+}
+
+/// Creates a 'RemoveAllowedProgram' instruction.
+pub fn remove_allowed_program(
+    program_id: Pubkey,
+    incognito_proxy: Pubkey,
+    guardian: Pubkey,
+    program: Pubkey,
+) -> Instruction {
+    let (allowlist, _) = find_allowlist_address(&program_id);
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(allowlist, false),
+            AccountMeta::new_readonly(incognito_proxy, false),
+            AccountMeta::new_readonly(guardian, true),
+        ],
+        data: BridgeInstruction::RemoveAllowedProgram { program }.pack(),
+    }
+}
+
+/// Derives the allowlist of destination programs for dapp interaction.
+pub fn find_allowlist_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ALLOWLIST_SEED], program_id)
+}
+
+/// Derives the vault authority which owns every vault token account of an incognito proxy.
+pub fn get_vault_authority_address(program_id: &Pubkey, incognito_proxy: &Pubkey, bump_seed: u8) -> Pubkey {
+    Pubkey::create_program_address(&[incognito_proxy.as_ref(), &[bump_seed]], program_id)
+        .expect("invalid incognito proxy bump seed")
+}
+
+/// Derives the account which marks a burn tx id as unshielded.
+pub fn find_burn_tx_address(program_id: &Pubkey, incognito_proxy: &Pubkey, tx_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BURN_TX_SEED, incognito_proxy.as_ref(), tx_id], program_id)
+}
+
+/// Derives the account which stores the instruction root of a verified beacon block.
+///
+/// The account is keyed by the instruction root rather than the beacon height, the height is
+/// bound by the instruction leaves, so a block verified by anyone cannot shadow another one.
+pub fn find_verified_root_address(program_id: &Pubkey, incognito_proxy: &Pubkey, inst_root: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VERIFIED_ROOT_SEED, incognito_proxy.as_ref(), inst_root], program_id)
+}
+
+/// Derives the signer authority which holds a user's tokens between dapp interactions.
+pub fn find_signer_authority_address(program_id: &Pubkey, signer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[signer.as_ref()], program_id)
+}
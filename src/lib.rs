@@ -1,7 +1,12 @@
+pub mod beacon;
 pub mod error;
+pub mod event;
 pub mod instruction;
 pub mod processor;
+pub mod proof;
+pub mod quorum;
 pub mod state;
+pub mod validation;
 
 #[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
\ No newline at end of file
@@ -1,29 +1,28 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    entrypoint::ProgramResult,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
     msg,
-    program::{invoke, invoke_signed},
+    program::{get_return_data, invoke, invoke_signed, set_return_data},
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack},
     pubkey::Pubkey,
     sysvar::{rent::Rent, Sysvar},
     instruction::{Instruction, AccountMeta},
-    secp256k1_recover::secp256k1_recover,
-    keccak::hash,
     borsh::try_from_slice_unchecked,
+    system_instruction,
 };
-use std::{
-    collections::BTreeMap,
-    str,
-};
+use std::collections::BTreeMap;
 use borsh::{BorshSerialize, BorshDeserialize};
-use spl_token::state::Account as TokenAccount;
-use arrayref::{array_refs, array_ref};
-use crate::{error::BridgeError, instruction::BridgeInstruction, state::{UnshieldRequest, IncognitoProxy, Vault}};
-use crate::state::{DappRequest};
-use spl_associated_token_account::{get_associated_token_address};
+use crate::{beacon::{BeaconInstruction, QUORUM_THRESHOLD_META_TYPE, SWAP_BEACON_META_TYPE, UNSHIELD_META_TYPE, UNSHIELD_TO_PDA_META_TYPE}, error::{dapp_error, BridgeError}, event::{inc_address_to_string, BridgeEvent}, instruction::BridgeInstruction, proof, quorum, state::{UnshieldRequest, IncognitoProxy, InitBeaconRequest, Vault, BeaconInstructionRequest, MAX_BEACON_ADDRESSES, BURN_TX_SEED, BURN_TX_LEN, DappAllowlist, ALLOWLIST_SEED, MAX_ALLOWED_PROGRAMS, QuorumThreshold, ProofBuffer, BeaconBlockRequest, VerifiedUnshieldRequest, Secp256k1UnshieldRequest, VERIFIED_ROOT_SEED, VERIFIED_ROOT_LEN, LEGACY_PROXY_VERSION}};
+use crate::state::{DappRequest, BatchDappRequest};
+use crate::validation::{
+    assert_associated_token_program, assert_incognito_proxy, assert_owned_by, assert_pda, assert_signer, assert_signer_authority,
+    assert_system_program, assert_token_account, assert_token_program, assert_upgrade_authority, assert_vault_authority,
+    assert_vault_token_account, assert_writable,
+};
+use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
 
-const LEN: usize = 1 + 1 + 32 + 32 + 32 + 32; // ignore last 32 bytes in instruction
+const UNSHIELD_ACCOUNTS_LEN: usize = 14;
 
 pub fn process_instruction(
         program_id: &Pubkey,
@@ -53,6 +52,86 @@ pub fn process_instruction(
             msg!("Instruction: Withdraw Request");
             process_withdraw_request(accounts, amount, inc_address, program_id)
         }
+        BridgeInstruction::SwapBeaconCommittee { swap_info } => {
+            msg!("Instruction: Swap Beacon Committee");
+            process_swap_beacon_committee(accounts, swap_info, program_id)
+        }
+        BridgeInstruction::ShieldNative { amount, inc_address } => {
+            msg!("Instruction: Shield Native");
+            process_shield_native(accounts, amount, inc_address, program_id)
+        }
+        BridgeInstruction::Pause => {
+            msg!("Instruction: Pause");
+            process_set_paused(accounts, true, program_id)
+        }
+        BridgeInstruction::Unpause => {
+            msg!("Instruction: Unpause");
+            process_set_paused(accounts, false, program_id)
+        }
+        BridgeInstruction::AddAllowedProgram { program } => {
+            msg!("Instruction: Add Allowed Program");
+            process_add_allowed_program(accounts, program, program_id)
+        }
+        BridgeInstruction::RemoveAllowedProgram { program } => {
+            msg!("Instruction: Remove Allowed Program");
+            process_remove_allowed_program(accounts, program, program_id)
+        }
+        BridgeInstruction::BatchDappInteraction { batch_request } => {
+            msg!("Instruction: Batch Dapp Interaction");
+            process_batch_dapp_interaction(accounts, batch_request, program_id)
+        }
+        BridgeInstruction::UnshieldAndCall { unshield_info, dapp_request } => {
+            msg!("Instruction: Unshield And Call");
+            process_unshield_and_call(accounts, unshield_info, dapp_request, program_id)
+        }
+        BridgeInstruction::WithdrawAll { inc_address, close_accounts } => {
+            msg!("Instruction: Withdraw All");
+            process_withdraw_all(accounts, inc_address, close_accounts, program_id)
+        }
+        BridgeInstruction::SetQuorumThreshold { threshold_info } => {
+            msg!("Instruction: Set Quorum Threshold");
+            process_set_quorum_threshold(accounts, threshold_info, program_id)
+        }
+        BridgeInstruction::ResizeProxy { capacity } => {
+            msg!("Instruction: Resize Proxy");
+            process_resize_proxy(accounts, capacity, program_id)
+        }
+        BridgeInstruction::InitProofBuffer => {
+            msg!("Instruction: Init Proof Buffer");
+            process_init_proof_buffer(accounts, program_id)
+        }
+        BridgeInstruction::WriteProofChunk { offset, data } => {
+            msg!("Instruction: Write Proof Chunk");
+            process_write_proof_chunk(accounts, offset, data, program_id)
+        }
+        BridgeInstruction::UnshieldFromBuffer => {
+            msg!("Instruction: Unshield From Buffer");
+            process_unshield_from_buffer(accounts, program_id)
+        }
+        BridgeInstruction::VerifyBeaconBlock { block_info } => {
+            msg!("Instruction: Verify Beacon Block");
+            process_verify_beacon_block(accounts, block_info, program_id)
+        }
+        BridgeInstruction::UnshieldVerified { unshield_info } => {
+            msg!("Instruction: Unshield Verified");
+            process_unshield_verified(accounts, unshield_info, program_id)
+        }
+        BridgeInstruction::UnshieldSecp256k1 { unshield_info } => {
+            msg!("Instruction: Unshield Secp256k1");
+            process_unshield_secp256k1(accounts, unshield_info, program_id)
+        }
+        BridgeInstruction::InitAllowlist => {
+            msg!("Instruction: Init Allowlist");
+            process_init_allowlist(accounts, program_id)
+        }
+        BridgeInstruction::MigrateProxy => {
+            msg!("Instruction: Migrate Proxy");
+            process_migrate_proxy(accounts, program_id)
+        }
+        BridgeInstruction::CloseProofBuffer => {
+            msg!("Instruction: Close Proof Buffer");
+            process_close_proof_buffer(accounts, program_id)
+        }
     }
 }
 
@@ -67,25 +146,22 @@ fn process_shield(
     let vault_token_account = next_account_info(account_info_iter)?;
     let incognito_proxy = next_account_info(account_info_iter)?;
     let shied_maker = next_account_info(account_info_iter)?;
-    if !shied_maker.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
     let token_program = next_account_info(account_info_iter)?;
+    assert_signer(shied_maker)?;
+    assert_writable(shield_maker_token_account)?;
+    assert_writable(vault_token_account)?;
+    assert_token_program(token_program)?;
 
-    if incognito_proxy.owner != program_id {
-        msg!("Invalid incognito proxy");
-        return Err(ProgramError::IncorrectProgramId);
-    }
+    let incognito_proxy_info = assert_incognito_proxy(incognito_proxy, program_id)?;
+    _verify_not_paused(&incognito_proxy_info)?;
 
-    if *vault_token_account.owner != spl_token::id() {
-        msg!("Vault token account must be owned by spl token");
-        return Err(ProgramError::IncorrectProgramId);
-    }
+    let token_id = assert_vault_token_account(
+        vault_token_account,
+        incognito_proxy.key,
+        incognito_proxy_info.bump_seed,
+        program_id)?;
 
-    let token_id = _verify_vault_token_account(
-        incognito_proxy.clone(),
-        vault_token_account.clone(),
-        program_id.clone())?;
+    let inc_address = inc_address_to_string(&inc_address)?;
 
     spl_token_transfer(TokenTransferParams {
         source: shield_maker_token_account.clone(),
@@ -96,7 +172,66 @@ fn process_shield(
         token_program: token_program.clone(),
     })?;
     msg!("Issue pToken to incognitoproxy,address,token,amount:{},{},{},{}",
-        incognito_proxy.key,str::from_utf8(&inc_address[..]).unwrap(), token_id, amount);
+        incognito_proxy.key, inc_address, token_id, amount);
+    BridgeEvent::Shield {
+        incognito_proxy: *incognito_proxy.key,
+        inc_address,
+        token: token_id,
+        amount,
+    }.emit();
+    Ok(())
+}
+
+// shield SOL by wrapping lamports directly into the vault native mint token account
+fn process_shield_native(
+    accounts: &[AccountInfo],
+    amount: u64,
+    inc_address: [u8; 148],
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let shield_maker = next_account_info(account_info_iter)?;
+    let vault_token_account = next_account_info(account_info_iter)?;
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    assert_signer(shield_maker)?;
+    assert_writable(shield_maker)?;
+    assert_writable(vault_token_account)?;
+    assert_system_program(system_program)?;
+    assert_token_program(token_program)?;
+
+    let incognito_proxy_info = assert_incognito_proxy(incognito_proxy, program_id)?;
+    _verify_not_paused(&incognito_proxy_info)?;
+
+    let token_id = assert_vault_token_account(
+        vault_token_account,
+        incognito_proxy.key,
+        incognito_proxy_info.bump_seed,
+        program_id)?;
+    if token_id != spl_token::native_mint::id() {
+        msg!("Vault token account must hold native mint {}", token_id);
+        return Err(BridgeError::InvalidKeysInInstruction.into());
+    }
+
+    let inc_address = inc_address_to_string(&inc_address)?;
+
+    invoke(
+        &system_instruction::transfer(shield_maker.key, vault_token_account.key, amount),
+        &[shield_maker.clone(), vault_token_account.clone(), system_program.clone()],
+    )?;
+    invoke(
+        &spl_token::instruction::sync_native(token_program.key, vault_token_account.key)?,
+        &[vault_token_account.clone(), token_program.clone()],
+    )?;
+    msg!("Issue pToken to incognitoproxy,address,token,amount:{},{},{},{}",
+        incognito_proxy.key, inc_address, token_id, amount);
+    BridgeEvent::Shield {
+        incognito_proxy: *incognito_proxy.key,
+        inc_address,
+        token: token_id,
+        amount,
+    }.emit();
     Ok(())
 }
 
@@ -113,6 +248,98 @@ fn process_unshield(
     accounts: &[AccountInfo],
     unshield_info: UnshieldRequest,
     program_id: &Pubkey,
+) -> ProgramResult {
+    let UnshieldRequest {
+        inst,
+        height,
+        inst_paths,
+        inst_path_is_lefts,
+        inst_root,
+        blk_data,
+        indexes,
+        signatures,
+    } = unshield_info;
+    _process_unshield(
+        accounts,
+        VerifiedUnshieldRequest { inst, height, inst_paths, inst_path_is_lefts, inst_root },
+        |_, incognito_proxy_info| {
+            // verify beacon signature
+            quorum::verify_beacon_signatures(
+                &incognito_proxy_info.beacons,
+                incognito_proxy_info.quorum_threshold,
+                &blk_data,
+                &inst_root,
+                &indexes,
+                &signatures,
+            )
+        },
+        program_id,
+    )
+}
+
+// unshield from a beacon block verified in advance, only the merkle inclusion is checked
+fn process_unshield_verified(
+    accounts: &[AccountInfo],
+    unshield_info: VerifiedUnshieldRequest,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    if accounts.len() <= UNSHIELD_ACCOUNTS_LEN {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let (unshield_accounts, verified_root_accounts) = accounts.split_at(UNSHIELD_ACCOUNTS_LEN);
+    let verified_root = &verified_root_accounts[0];
+    let inst_root = unshield_info.inst_root;
+    _process_unshield(
+        unshield_accounts,
+        unshield_info,
+        |incognito_proxy, _| _verify_root_stored(verified_root, incognito_proxy.key, &inst_root, program_id),
+        program_id,
+    )
+}
+
+// unshield with beacon signatures checked by the preceding secp256k1 program instruction
+fn process_unshield_secp256k1(
+    accounts: &[AccountInfo],
+    unshield_info: Secp256k1UnshieldRequest,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    if accounts.len() <= UNSHIELD_ACCOUNTS_LEN {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let (unshield_accounts, instructions_accounts) = accounts.split_at(UNSHIELD_ACCOUNTS_LEN);
+    let instructions_sysvar = &instructions_accounts[0];
+    let Secp256k1UnshieldRequest {
+        inst,
+        height,
+        inst_paths,
+        inst_path_is_lefts,
+        inst_root,
+        blk_data,
+        indexes,
+    } = unshield_info;
+    _process_unshield(
+        unshield_accounts,
+        VerifiedUnshieldRequest { inst, height, inst_paths, inst_path_is_lefts, inst_root },
+        |_, incognito_proxy_info| {
+            quorum::verify_secp256k1_signatures(
+                &incognito_proxy_info.beacons,
+                incognito_proxy_info.quorum_threshold,
+                &blk_data,
+                &inst_root,
+                &indexes,
+                instructions_sysvar,
+            )
+        },
+        program_id,
+    )
+}
+
+// `verify_root` checks the instruction root of the beacon block is trusted
+fn _process_unshield(
+    accounts: &[AccountInfo],
+    unshield_info: VerifiedUnshieldRequest,
+    verify_root: impl FnOnce(&AccountInfo, &IncognitoProxy) -> ProgramResult,
+    program_id: &Pubkey,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let vault_token_account = next_account_info(account_info_iter)?;
@@ -122,64 +349,60 @@ fn process_unshield(
     let incognito_proxy = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
     let unshield_token_account = next_account_info(account_info_iter)?;
-    let incognito_proxy_info = IncognitoProxy::unpack_unchecked(&incognito_proxy.data.borrow())?;
-    if !incognito_proxy_info.is_initialized() {
-        return Err(BridgeError::BeaconsUnInitialized.into())
+    let burn_tx_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let receiver_owner = next_account_info(account_info_iter)?;
+    let token_mint = next_account_info(account_info_iter)?;
+    let associated_token_program = next_account_info(account_info_iter)?;
+    let rent_sysvar = next_account_info(account_info_iter)?;
+    assert_writable(vault_token_account)?;
+    assert_writable(unshield_token_account)?;
+    assert_writable(burn_tx_account)?;
+    assert_signer(payer)?;
+    assert_writable(payer)?;
+    assert_token_program(token_program)?;
+    assert_system_program(system_program)?;
+    assert_associated_token_program(associated_token_program)?;
+
+    let incognito_proxy_info = assert_incognito_proxy(incognito_proxy, program_id)?;
+    _verify_not_paused(&incognito_proxy_info)?;
+    // blocks before the start height were signed by a previous committee
+    if unshield_info.height < incognito_proxy_info.start_height {
+        msg!("Unshield height {} before committee start height {}", unshield_info.height, incognito_proxy_info.start_height);
+        return Err(BridgeError::UnshieldBeforeStartHeight.into());
     }
 
     if incognito_proxy_info.vault != *vault_account.key {
         msg!("Send to wrong vault account");
-        return Err(ProgramError::IncorrectProgramId);
-    }
-
-    if incognito_proxy.owner != program_id {
-        msg!("Invalid incognito proxy");
-        return Err(ProgramError::IncorrectProgramId);
+        return Err(BridgeError::InvalidMapAccount.into());
     }
+    assert_vault_authority(
+        vault_authority_account,
+        incognito_proxy.key,
+        incognito_proxy_info.bump_seed,
+        program_id,
+    )?;
 
     // extract data from input
     let inst = unshield_info.inst;
-    if inst.len() < LEN {
-        msg!("Invalid instruction input");
-        return Err(BridgeError::InvalidBeaconInstruction.into());
-    }
-    let inst_ = array_ref![inst, 0, LEN];
-    #[allow(clippy::ptr_offset_with_cast)]
-    let (
-        meta_type,
-        shard_id,
-        token,
-        receiver_key,
-        _,
-        unshield_amount,
-        tx_id, // todo: store this data
-    ) = array_refs![
-        inst_,
-        1,
-        1,
-        32,
-        32,
-        24,
-        8,
-        32
-    ];
-    let meta_type = u8::from_le_bytes(*meta_type);
-    let shard_id = u8::from_le_bytes(*shard_id);
-    let token_key = Pubkey::new(token);
-    let receiver_key = Pubkey::new(receiver_key);
-    let unshield_amount_u64 = u64::from_be_bytes(*unshield_amount);
-
-    // validate metatype and key provided
-    if (meta_type != 157 && meta_type != 158) || shard_id != 1 {
-        msg!("Invalid beacon instruction metatype {}, {}", meta_type, shard_id);
-        return Err(BridgeError::InvalidKeysInInstruction.into());
-    }
+    let beacon_inst = _decode_beacon_inst(&inst, &[UNSHIELD_META_TYPE, UNSHIELD_TO_PDA_META_TYPE])?;
+    let meta_type = beacon_inst.meta_type();
+    let unshield = match beacon_inst {
+        BeaconInstruction::Unshield(unshield) | BeaconInstruction::UnshieldToPda(unshield) => unshield,
+        _ => return Err(BridgeError::InvalidMetaType.into()),
+    };
+    let token_key = unshield.token;
+    let receiver_key = unshield.receiver;
+    let unshield_amount_u64 = unshield.amount.to_u64()?;
+    let tx_id = &unshield.tx_id;
 
     // verify vault token account
-    let token_id = _verify_vault_token_account(
-        incognito_proxy.clone(),
-        vault_token_account.clone(),
-        program_id.clone())?;
+    let token_id = assert_vault_token_account(
+        vault_token_account,
+        incognito_proxy.key,
+        incognito_proxy_info.bump_seed,
+        program_id)?;
     if token_key != token_id {
         msg!("Token key and key provided not match {}, {}", token_key, token_id);
         return Err(BridgeError::InvalidKeysInInstruction.into());
@@ -190,48 +413,40 @@ fn process_unshield(
         return Err(BridgeError::InvalidKeysInInstruction.into());
     }
 
-    // verify beacon signature
-    if unshield_info.indexes.len() != unshield_info.signatures.len() {
-        msg!("Invalid instruction provided, length of indexes and signatures not match");
-        return Err(BridgeError::InvalidBeaconInstruction.into());
-    }
-
-    if unshield_info.signatures.len() <= incognito_proxy_info.beacons.len() * 2 / 3 {
-        msg!("Invalid instruction input");
-        return Err(BridgeError::InvalidNumberOfSignature.into());
+    if token_key != *token_mint.key {
+        msg!("Token key and mint provided not match {}, {}", token_key, *token_mint.key);
+        return Err(BridgeError::InvalidKeysInInstruction.into());
     }
 
-    let mut blk_data_bytes = unshield_info.blk_data.to_vec();
-    blk_data_bytes.extend_from_slice(&unshield_info.inst_root);
-    // Get double block hash from instRoot and other data
-    let blk = hash(&hash(&blk_data_bytes[..]).to_bytes());
-
-    for i in 0..unshield_info.indexes.len() {
-        let s_r_v = unshield_info.signatures[i];
-        let (s_r, v) = s_r_v.split_at(64);
-        if v.len() != 1 {
-            msg!("Invalid signature v input");
-            return Err(BridgeError::InvalidBeaconSignature.into());
+    // verify the receiver token account before any tokens move
+    let is_wsol = meta_type == UNSHIELD_META_TYPE && token_key == spl_token::native_mint::id();
+    if is_wsol {
+        if *vault_token_account.key == *unshield_token_account.key {
+            msg!("Invalid sender and receiver in unshield request");
+            return Err(BridgeError::InvalidTransferTokenData.into());
+        }
+    } else {
+        let receiver_owner_key = match meta_type {
+            UNSHIELD_META_TYPE => *unshield_maker.key,
+            UNSHIELD_TO_PDA_META_TYPE => Pubkey::find_program_address(&[unshield_maker.key.as_ref()], program_id).0,
+            _ => return Err(BridgeError::InvalidMetaType.into()),
+        };
+        if receiver_owner_key != *receiver_owner.key {
+            msg!("Receiver token account owner and key provided not match {}, {}", receiver_owner_key, *receiver_owner.key);
+            return Err(BridgeError::InvalidKeysInInstruction.into());
         }
-        let beacon_key_from_signature_result = secp256k1_recover(
-            &blk.to_bytes()[..],
-            v[0],
-            s_r,
-        ).unwrap();
-        let index_beacon = unshield_info.indexes[i];
-        let beacon_key = incognito_proxy_info.beacons[index_beacon as usize];
-        if beacon_key_from_signature_result != beacon_key {
-            return Err(BridgeError::InvalidBeaconSignature.into());
+        let receiver_associated_acc = get_associated_token_address(&receiver_owner_key, &token_key);
+        if receiver_associated_acc != *unshield_token_account.key {
+            msg!("Receiver's associated token account and key provided not match {}, {}", receiver_associated_acc, *unshield_token_account.key);
+            return Err(BridgeError::InvalidKeysInInstruction.into());
         }
     }
 
-    // append block height to instruction
-    let height_vec = append_at_top(unshield_info.height);
-    let mut inst_vec = inst.to_vec();
-    inst_vec.extend_from_slice(&height_vec);
-    let inst_hash = hash(&inst_vec[..]);
-    if !instruction_in_merkle_tree(
-        &inst_hash.to_bytes(),
+    verify_root(incognito_proxy, &incognito_proxy_info)?;
+
+    // verify instruction merkle tree
+    if !proof::instruction_in_merkle_tree(
+        &proof::instruction_hash(&inst, unshield_info.height),
         &unshield_info.inst_root,
         &unshield_info.inst_paths,
         &unshield_info.inst_path_is_lefts
@@ -240,7 +455,31 @@ fn process_unshield(
         return Err(BridgeError::InvalidBeaconMerkleTree.into());
     }
 
-    _process_insert_entry(vault_account, program_id, tx_id)?;
+    _verify_legacy_entry(vault_account, program_id, tx_id)?;
+    _process_insert_entry(
+        burn_tx_account,
+        payer,
+        system_program,
+        incognito_proxy.key,
+        program_id,
+        tx_id,
+    )?;
+
+    if !is_wsol && unshield_token_account.data_is_empty() {
+        invoke(
+            &create_associated_token_account(payer.key, receiver_owner.key, token_mint.key),
+            &[
+                payer.clone(),
+                unshield_token_account.clone(),
+                receiver_owner.clone(),
+                token_mint.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                rent_sysvar.clone(),
+                associated_token_program.clone(),
+            ],
+        )?;
+    }
 
     // prepare to transfer token to user
     let authority_signer_seeds = &[
@@ -258,76 +497,68 @@ fn process_unshield(
         token_program: token_program.clone(),
     })?;
 
-    match meta_type {
-        157 => {
-            let is_wsol = token_key == spl_token::native_mint::id();
-            if !is_wsol {
-                let unshield_maker_associated_acc = get_associated_token_address(
-                    &unshield_maker.key,
-                    &token_key
-                );
-
-                if unshield_maker_associated_acc != *unshield_token_account.key {
-                    msg!("unshield maker's associated token account and key provided not match {}, {}", unshield_maker_associated_acc, *unshield_token_account.key);
-                    return Err(BridgeError::InvalidKeysInInstruction.into());
-                }
-            } else {
-                // handle native token
-                if *vault_token_account.key == *unshield_token_account.key {
-                    msg!("Invalid sender and receiver in unshield request");
-                    return Err(BridgeError::InvalidTransferTokenData.into());
-                }
-                // close account
-                spl_close_token_acc(TokenCloseParams {
-                    account: unshield_token_account.clone(),
-                    destination: unshield_maker.clone(),
-                    authority: vault_authority_account.clone(),
-                    authority_signer_seeds,
-                    token_program: token_program.clone(),
-                })?;
-            }
-        },
-        158 => {
-            let (pda, _) = Pubkey::find_program_address(
-                &[unshield_maker.key.as_ref()],
-                program_id
-            );
-            let pda_associated_token_acc = get_associated_token_address(&pda, &token_key);
-            if pda_associated_token_acc != *unshield_token_account.key {
-                msg!("pda's associated token account and key provided not match {}, {}", pda_associated_token_acc, *unshield_token_account.key);
-                return Err(BridgeError::InvalidKeysInInstruction.into());
-            }
-        },
-        _ => {
-            return Err(BridgeError::InvalidMetaType.into());
-        }
+    // handle native token
+    if is_wsol {
+        spl_close_token_acc(TokenCloseParams {
+            account: unshield_token_account.clone(),
+            destination: unshield_maker.clone(),
+            authority: vault_authority_account.clone(),
+            authority_signer_seeds,
+            token_program: token_program.clone(),
+        })?;
     }
 
+    BridgeEvent::Unshield {
+        incognito_proxy: *incognito_proxy.key,
+        tx_id: *tx_id,
+        meta_type,
+        receiver: receiver_key,
+        token: token_key,
+        amount: unshield_amount_u64,
+    }.emit();
+
     Ok(())
 }
 
 // add logic to proccess init beacon list
 fn process_init_beacon(
     accounts: &[AccountInfo],
-    init_beacon_info: IncognitoProxy,
+    init_beacon_info: InitBeaconRequest,
     program_id: &Pubkey,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
     let incognito_proxy = next_account_info(account_info_iter)?;
     let vault_acc = next_account_info(account_info_iter)?;
+    let guardian = next_account_info(account_info_iter)?;
+    assert_signer(guardian)?;
+    assert_writable(incognito_proxy)?;
+    assert_owned_by(incognito_proxy, program_id)?;
+    assert_owned_by(vault_acc, program_id)?;
     assert_rent_exempt(rent, incognito_proxy)?;
     assert_rent_exempt(rent, vault_acc)?;
-    let mut incognito_proxy_info = assert_uninitialized::<IncognitoProxy>(incognito_proxy)?;
-    if incognito_proxy.owner != program_id {
-        msg!("Invalid incognito proxy");
-        return Err(ProgramError::IncorrectProgramId);
+    let mut incognito_proxy_info = IncognitoProxy::unpack_unchecked(&incognito_proxy.data.borrow())?;
+    if incognito_proxy_info.is_initialized() {
+        return Err(BridgeError::AccInitialized.into());
+    }
+    let capacity = IncognitoProxy::capacity(incognito_proxy.data_len());
+    if init_beacon_info.beacons.len() > capacity {
+        msg!("{} beacons exceed capacity {}", init_beacon_info.beacons.len(), capacity);
+        return Err(BridgeError::BeaconCapacityExceeded.into());
+    }
+    if !init_beacon_info.quorum_threshold.is_valid() {
+        msg!("Invalid quorum threshold {:?}", init_beacon_info.quorum_threshold);
+        return Err(BridgeError::InvalidQuorumThreshold.into());
     }
 
-    incognito_proxy_info.is_initialized = init_beacon_info.is_initialized;
+    incognito_proxy_info.is_initialized = true;
     incognito_proxy_info.bump_seed = init_beacon_info.bump_seed;
     incognito_proxy_info.vault = init_beacon_info.vault;
     incognito_proxy_info.beacons = init_beacon_info.beacons;
+    incognito_proxy_info.guardian = *guardian.key;
+    incognito_proxy_info.is_paused = false;
+    incognito_proxy_info.quorum_threshold = init_beacon_info.quorum_threshold;
+    incognito_proxy_info.threshold_height = 0;
     IncognitoProxy::pack(incognito_proxy_info, &mut incognito_proxy.data.borrow_mut())?;
     _process_init_map(vault_acc)?;
 
@@ -345,31 +576,30 @@ fn process_withdraw_request(
     let vault_token_account = next_account_info(account_info_iter)?;
     let incognito_proxy = next_account_info(account_info_iter)?;
     let signer = next_account_info(account_info_iter)?;
-    if !signer.is_signer {
-        return Err(BridgeError::InvalidSigner.into());
-    }
     let signer_authority_token = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
+    assert_signer(signer)?;
+    assert_writable(signer_token_account)?;
+    assert_writable(vault_token_account)?;
+    assert_token_program(token_program)?;
 
-    // verify vault token account
-    let token_id = _verify_vault_token_account(
-        incognito_proxy.clone(),
-        vault_token_account.clone(),
-        program_id.clone())?;
+    let incognito_proxy_info = assert_incognito_proxy(incognito_proxy, program_id)?;
+    _verify_not_paused(&incognito_proxy_info)?;
 
-    let (pda, bump) = Pubkey::find_program_address(
-        &[signer.key.as_ref()],
-        program_id
-    );
+    // verify vault token account
+    let token_id = assert_vault_token_account(
+        vault_token_account,
+        incognito_proxy.key,
+        incognito_proxy_info.bump_seed,
+        program_id)?;
 
-    if pda != *signer_authority_token.key {
-        return Err(BridgeError::InvalidSignerTokenAuth.into());
-    }
+    let bump = assert_signer_authority(signer_authority_token, signer.key, program_id)?;
 
     let authority_signer_seeds = &[
         signer.key.as_ref(),
         &[bump],
     ];
+    let inc_address = inc_address_to_string(&inc_address)?;
 
     spl_token_transfer(TokenTransferParams {
         source: signer_token_account.clone(),
@@ -380,7 +610,94 @@ fn process_withdraw_request(
         token_program: token_program.clone(),
     })?;
 
-    msg!("Issue pToken to incognitoproxy,address,token,amount:{},{},{},{}", incognito_proxy.key,str::from_utf8(&inc_address[..]).unwrap(), token_id, amount);
+    msg!("Issue pToken to incognitoproxy,address,token,amount:{},{},{},{}", incognito_proxy.key, inc_address, token_id, amount);
+    BridgeEvent::Withdraw {
+        incognito_proxy: *incognito_proxy.key,
+        signer: *signer.key,
+        inc_address,
+        token: token_id,
+        amount,
+    }.emit();
+
+    Ok(())
+}
+
+// sweep signer authority token accounts back to the vaults
+fn process_withdraw_all(
+    accounts: &[AccountInfo],
+    inc_address: [u8; 148],
+    close_accounts: bool,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    let signer = next_account_info(account_info_iter)?;
+    let signer_authority_token = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    assert_signer(signer)?;
+    if close_accounts {
+        assert_writable(signer)?;
+    }
+    assert_token_program(token_program)?;
+    let token_accounts = account_info_iter.as_slice();
+    let token_account_pairs = token_accounts.chunks_exact(2);
+    if token_accounts.is_empty() || !token_account_pairs.remainder().is_empty() {
+        msg!("Token accounts must come in pairs of signer authority and vault accounts");
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let incognito_proxy_info = assert_incognito_proxy(incognito_proxy, program_id)?;
+    _verify_not_paused(&incognito_proxy_info)?;
+
+    let bump = assert_signer_authority(signer_authority_token, signer.key, program_id)?;
+
+    let authority_signer_seeds = &[
+        signer.key.as_ref(),
+        &[bump],
+    ];
+    let inc_address = inc_address_to_string(&inc_address)?;
+
+    for token_account_pair in token_account_pairs {
+        let (signer_token_account, vault_token_account) = (&token_account_pair[0], &token_account_pair[1]);
+        assert_writable(signer_token_account)?;
+        assert_writable(vault_token_account)?;
+        let token_id = assert_vault_token_account(
+            vault_token_account,
+            incognito_proxy.key,
+            incognito_proxy_info.bump_seed,
+            program_id)?;
+        let amount = assert_token_account(signer_token_account)?.amount;
+
+        if amount > 0 {
+            spl_token_transfer(TokenTransferParams {
+                source: signer_token_account.clone(),
+                destination: vault_token_account.clone(),
+                amount,
+                authority: signer_authority_token.clone(),
+                authority_signer_seeds,
+                token_program: token_program.clone(),
+            })?;
+
+            msg!("Issue pToken to incognitoproxy,address,token,amount:{},{},{},{}", incognito_proxy.key, inc_address, token_id, amount);
+            BridgeEvent::Withdraw {
+                incognito_proxy: *incognito_proxy.key,
+                signer: *signer.key,
+                inc_address: inc_address.clone(),
+                token: token_id,
+                amount,
+            }.emit();
+        }
+
+        if close_accounts {
+            spl_close_token_acc(TokenCloseParams {
+                account: signer_token_account.clone(),
+                destination: signer.clone(),
+                authority: signer_authority_token.clone(),
+                authority_signer_seeds,
+                token_program: token_program.clone(),
+            })?;
+        }
+    }
 
     Ok(())
 }
@@ -392,9 +709,7 @@ fn process_dapp_interaction(
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let signer = next_account_info(account_info_iter)?;
-    if !signer.is_signer {
-        return Err(BridgeError::InvalidSigner.into());
-    }
+    assert_signer(signer)?;
     let (_, bump) = Pubkey::find_program_address(
         &[signer.key.as_ref()],
         program_id
@@ -418,17 +733,636 @@ fn process_dapp_interaction(
         }
     }
     let program_dest = next_account_info(account_info_iter)?;
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    let allowlist = next_account_info(account_info_iter)?;
+    // the pause of the proxy bound to the allowlist applies, not of any proxy provided
+    let allowlist_info = _verify_allowlist(allowlist, incognito_proxy.key, program_id)?;
+    let incognito_proxy_info = assert_incognito_proxy(incognito_proxy, program_id)?;
+    _verify_not_paused(&incognito_proxy_info)?;
+    _verify_program_allowed(&allowlist_info, program_dest.key)?;
+
+    let pre_balances = dapp_request.max_spends
+        .iter()
+        .map(|assertion| _token_balance(&accounts_info, assertion.account_index))
+        .collect::<Result<Vec<u64>, ProgramError>>()?;
 
     let new_inst = Instruction {
         program_id: *program_dest.key,
         accounts: accounts_invoke.to_vec(),
         data: dapp_request.inst,
     };
-    invoke_signed(
-        &new_inst,
-        &accounts_info[..],
-        &[authority_signer_seeds],
-    ).unwrap();
+    _invoke_dapp(&new_inst, &accounts_info, authority_signer_seeds)?;
+
+    // slippage limits requested by the signer
+    for assertion in &dapp_request.min_balances {
+        let balance = _token_balance(&accounts_info, assertion.account_index)?;
+        if balance < assertion.amount {
+            msg!("Balance {} below minimum {}", balance, assertion.amount);
+            return Err(BridgeError::BalanceBelowMinimum.into());
+        }
+    }
+    for (assertion, pre_balance) in dapp_request.max_spends.iter().zip(pre_balances) {
+        let spent = pre_balance.saturating_sub(_token_balance(&accounts_info, assertion.account_index)?);
+        if spent > assertion.amount {
+            msg!("Spent {} above maximum {}", spent, assertion.amount);
+            return Err(BridgeError::SpendAboveMaximum.into());
+        }
+    }
+
+    Ok(())
+}
+
+fn process_batch_dapp_interaction(
+    accounts: &[AccountInfo],
+    batch_request: BatchDappRequest,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer = next_account_info(account_info_iter)?;
+    assert_signer(signer)?;
+    let (_, bump) = Pubkey::find_program_address(
+        &[signer.key.as_ref()],
+        program_id
+    );
+
+    let authority_signer_seeds = &[
+        signer.key.as_ref(),
+        &[bump],
+    ];
+
+    let mut accounts_invoke = Vec::with_capacity(batch_request.num_acc as usize);
+    let mut accounts_info = Vec::with_capacity(batch_request.num_acc as usize);
+    for i in 0..batch_request.num_acc {
+        let next_acc: &AccountInfo = next_account_info(account_info_iter)?;
+        accounts_info.push(next_acc.clone());
+        let is_signer = next_acc.is_signer || batch_request.sign_index == i;
+        if !next_acc.is_writable {
+            accounts_invoke.push(AccountMeta::new_readonly(*next_acc.key, is_signer));
+        } else {
+            accounts_invoke.push(AccountMeta::new(*next_acc.key, is_signer));
+        }
+    }
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    let allowlist = next_account_info(account_info_iter)?;
+    // the pause of the proxy bound to the allowlist applies, not of any proxy provided
+    let allowlist_info = _verify_allowlist(allowlist, incognito_proxy.key, program_id)?;
+    let incognito_proxy_info = assert_incognito_proxy(incognito_proxy, program_id)?;
+    _verify_not_paused(&incognito_proxy_info)?;
+    if batch_request.calls.is_empty() {
+        msg!("Empty dapp batch");
+        return Err(BridgeError::InvalidDappCall.into());
+    }
+
+    // check every call before the first one runs
+    let mut new_insts = Vec::with_capacity(batch_request.calls.len());
+    for call in batch_request.calls {
+        let program_dest = accounts_info
+            .get(call.program_index as usize)
+            .ok_or(BridgeError::InvalidDappCall)?;
+        _verify_program_allowed(&allowlist_info, program_dest.key)?;
+        let mut call_accounts = Vec::new();
+        for (start, len) in call.account_ranges {
+            let range = start as usize..start as usize + len as usize;
+            call_accounts.extend_from_slice(accounts_invoke.get(range).ok_or(BridgeError::InvalidDappCall)?);
+        }
+        new_insts.push(Instruction {
+            program_id: *program_dest.key,
+            accounts: call_accounts,
+            data: call.inst,
+        });
+    }
+    for new_inst in &new_insts {
+        _invoke_dapp(new_inst, &accounts_info, authority_signer_seeds)?;
+    }
+
+    Ok(())
+}
+
+// replace beacon list by the committee signed in a swap beacon instruction
+fn process_swap_beacon_committee(
+    accounts: &[AccountInfo],
+    swap_info: BeaconInstructionRequest,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    assert_writable(incognito_proxy)?;
+    let mut incognito_proxy_info = assert_incognito_proxy(incognito_proxy, program_id)?;
+
+    // extract data from input
+    let (start_height, beacons) = match _decode_beacon_inst(&swap_info.inst, &[SWAP_BEACON_META_TYPE])? {
+        BeaconInstruction::SwapBeaconCommittee { start_height, beacons } => (start_height.to_u64()?, beacons),
+        _ => return Err(BridgeError::InvalidMetaType.into()),
+    };
+    let num_vals = beacons.len();
+    if num_vals == 0 || num_vals > MAX_BEACON_ADDRESSES {
+        msg!("Invalid number of beacons {}", num_vals);
+        return Err(BridgeError::InvalidBeaconInstruction.into());
+    }
+
+    if num_vals > IncognitoProxy::capacity(incognito_proxy.data_len()) {
+        msg!("Number of beacons {} exceeds proxy capacity, resize the proxy first", num_vals);
+        return Err(BridgeError::BeaconCapacityExceeded.into());
+    }
+
+    if start_height <= incognito_proxy_info.start_height {
+        msg!("New committee start height {} must be greater than {}", start_height, incognito_proxy_info.start_height);
+        return Err(BridgeError::InvalidCommitteeStartHeight.into());
+    }
+
+    // verify beacon signature by current committee
+    quorum::verify_beacon_signatures(
+        &incognito_proxy_info.beacons,
+        incognito_proxy_info.quorum_threshold,
+        &swap_info.blk_data,
+        &swap_info.inst_root,
+        &swap_info.indexes,
+        &swap_info.signatures,
+    )?;
+
+    // verify instruction merkle tree
+    if !proof::instruction_in_merkle_tree(
+        &proof::instruction_hash(&swap_info.inst, swap_info.height),
+        &swap_info.inst_root,
+        &swap_info.inst_paths,
+        &swap_info.inst_path_is_lefts
+    ) {
+        msg!("Invalid instruction root");
+        return Err(BridgeError::InvalidBeaconMerkleTree.into());
+    }
+
+    incognito_proxy_info.beacons = beacons;
+    incognito_proxy_info.start_height = start_height;
+    BridgeEvent::CommitteeUpdated {
+        incognito_proxy: *incognito_proxy.key,
+        start_height,
+        beacons: incognito_proxy_info.beacons.iter().map(|beacon| beacon.to_bytes()).collect(),
+    }.emit();
+    IncognitoProxy::pack(incognito_proxy_info, &mut incognito_proxy.data.borrow_mut())?;
+    msg!("Beacon committee swapped,start height,number of beacons:{},{}", start_height, num_vals);
+
+    Ok(())
+}
+
+// replace the quorum threshold with one signed by the current committee
+fn process_set_quorum_threshold(
+    accounts: &[AccountInfo],
+    threshold_info: BeaconInstructionRequest,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    assert_writable(incognito_proxy)?;
+    let mut incognito_proxy_info = assert_incognito_proxy(incognito_proxy, program_id)?;
+
+    // extract data from input
+    let (numerator, denominator) = match _decode_beacon_inst(&threshold_info.inst, &[QUORUM_THRESHOLD_META_TYPE])? {
+        BeaconInstruction::SetQuorumThreshold { numerator, denominator } => (numerator, denominator),
+        _ => return Err(BridgeError::InvalidMetaType.into()),
+    };
+    let quorum_threshold = QuorumThreshold {
+        numerator: u8::try_from(numerator.to_u64()?).map_err(|_| BridgeError::InvalidQuorumThreshold)?,
+        denominator: u8::try_from(denominator.to_u64()?).map_err(|_| BridgeError::InvalidQuorumThreshold)?,
+    };
+    if !quorum_threshold.is_valid() {
+        msg!("Invalid quorum threshold {:?}", quorum_threshold);
+        return Err(BridgeError::InvalidQuorumThreshold.into());
+    }
+
+    // a signed update cannot be replayed to restore an older threshold
+    if threshold_info.height <= incognito_proxy_info.threshold_height {
+        msg!("Quorum threshold height {} must be greater than {}", threshold_info.height, incognito_proxy_info.threshold_height);
+        return Err(BridgeError::StaleQuorumThreshold.into());
+    }
+
+    // verify beacon signature by current committee
+    quorum::verify_beacon_signatures(
+        &incognito_proxy_info.beacons,
+        incognito_proxy_info.quorum_threshold,
+        &threshold_info.blk_data,
+        &threshold_info.inst_root,
+        &threshold_info.indexes,
+        &threshold_info.signatures,
+    )?;
+
+    // verify instruction merkle tree
+    if !proof::instruction_in_merkle_tree(
+        &proof::instruction_hash(&threshold_info.inst, threshold_info.height),
+        &threshold_info.inst_root,
+        &threshold_info.inst_paths,
+        &threshold_info.inst_path_is_lefts
+    ) {
+        msg!("Invalid instruction root");
+        return Err(BridgeError::InvalidBeaconMerkleTree.into());
+    }
+
+    incognito_proxy_info.quorum_threshold = quorum_threshold;
+    incognito_proxy_info.threshold_height = threshold_info.height;
+    IncognitoProxy::pack(incognito_proxy_info, &mut incognito_proxy.data.borrow_mut())?;
+    BridgeEvent::QuorumThresholdUpdated {
+        incognito_proxy: *incognito_proxy.key,
+        numerator: quorum_threshold.numerator,
+        denominator: quorum_threshold.denominator,
+    }.emit();
+
+    Ok(())
+}
+
+// grow or shrink the beacon capacity of the incognito proxy
+fn process_resize_proxy(
+    accounts: &[AccountInfo],
+    capacity: u8,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    let guardian = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    assert_writable(incognito_proxy)?;
+    assert_signer(payer)?;
+    assert_writable(payer)?;
+    assert_system_program(system_program)?;
+    let incognito_proxy_info = _verify_guardian(incognito_proxy, guardian, program_id)?;
+
+    if (capacity as usize) < incognito_proxy_info.beacons.len() {
+        msg!("Capacity {} below number of beacons {}", capacity, incognito_proxy_info.beacons.len());
+        return Err(BridgeError::BeaconCapacityExceeded.into());
+    }
+    let space = IncognitoProxy::space(capacity as usize);
+    // the runtime caps the growth of an account per instruction
+    if space > incognito_proxy.data_len() + MAX_PERMITTED_DATA_INCREASE {
+        msg!("Resize to {} bytes exceeds the growth limit, resize in several steps", space);
+        return Err(BridgeError::ProxyGrowthExceeded.into());
+    }
+    let minimum_balance = Rent::get()?.minimum_balance(space);
+    let required_lamports = minimum_balance.saturating_sub(incognito_proxy.lamports());
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, incognito_proxy.key, required_lamports),
+            &[payer.clone(), incognito_proxy.clone(), system_program.clone()],
+        )?;
+    }
+    incognito_proxy.realloc(space, true)?;
+    // a shrunk proxy keeps only the rent of its new size, the excess goes back to the payer
+    let excess_lamports = incognito_proxy.lamports().saturating_sub(minimum_balance);
+    if excess_lamports > 0 {
+        let payer_lamports = payer
+            .lamports()
+            .checked_add(excess_lamports)
+            .ok_or(ProgramError::InvalidArgument)?;
+        **payer.lamports.borrow_mut() = payer_lamports;
+        **incognito_proxy.lamports.borrow_mut() = minimum_balance;
+    }
+    BridgeEvent::ProxyResized {
+        incognito_proxy: *incognito_proxy.key,
+        capacity,
+    }.emit();
+
+    Ok(())
+}
+
+// rewrite an unversioned incognito proxy with the current layout, the guardian is set by the upgrade authority
+fn process_migrate_proxy(
+    accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    let guardian = next_account_info(account_info_iter)?;
+    let upgrade_authority = next_account_info(account_info_iter)?;
+    let program_data = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    assert_writable(incognito_proxy)?;
+    assert_signer(guardian)?;
+    assert_signer(payer)?;
+    assert_writable(payer)?;
+    assert_system_program(system_program)?;
+    assert_upgrade_authority(program_data, upgrade_authority, program_id)?;
+    let mut incognito_proxy_info = assert_incognito_proxy(incognito_proxy, program_id)?;
+    if IncognitoProxy::version(&incognito_proxy.data.borrow())? != LEGACY_PROXY_VERSION {
+        msg!("Incognito proxy already has a versioned layout");
+        return Err(BridgeError::UnsupportedProxyVersion.into());
+    }
+
+    let space = IncognitoProxy::space(IncognitoProxy::LEGACY_CAPACITY);
+    let required_lamports = rent
+        .minimum_balance(space)
+        .saturating_sub(incognito_proxy.lamports());
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, incognito_proxy.key, required_lamports),
+            &[payer.clone(), incognito_proxy.clone(), system_program.clone()],
+        )?;
+    }
+    incognito_proxy.realloc(space, false)?;
+    incognito_proxy_info.guardian = *guardian.key;
+    IncognitoProxy::pack(incognito_proxy_info, &mut incognito_proxy.data.borrow_mut())?;
+
+    Ok(())
+}
+
+// guardian switch to stop the bridge
+fn process_set_paused(
+    accounts: &[AccountInfo],
+    is_paused: bool,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    let guardian = next_account_info(account_info_iter)?;
+    assert_writable(incognito_proxy)?;
+    let mut incognito_proxy_info = _verify_guardian(incognito_proxy, guardian, program_id)?;
+
+    incognito_proxy_info.is_paused = is_paused;
+    IncognitoProxy::pack(incognito_proxy_info, &mut incognito_proxy.data.borrow_mut())?;
+    BridgeEvent::PauseUpdated {
+        incognito_proxy: *incognito_proxy.key,
+        is_paused,
+    }.emit();
+
+    Ok(())
+}
+
+// unshield into the signer authority of the unshield maker then call a dapp as that authority
+fn process_unshield_and_call(
+    accounts: &[AccountInfo],
+    unshield_info: UnshieldRequest,
+    dapp_request: DappRequest,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    if accounts.len() <= UNSHIELD_ACCOUNTS_LEN {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let (unshield_accounts, dapp_accounts) = accounts.split_at(UNSHIELD_ACCOUNTS_LEN);
+    let unshield_maker = &unshield_accounts[1];
+    let signer = &dapp_accounts[0];
+    // the burn proof is public, only the receiver can choose the call made with the tokens
+    assert_signer(unshield_maker)?;
+    if signer.key != unshield_maker.key {
+        msg!("Unshield maker must sign the dapp call");
+        return Err(BridgeError::InvalidSigner.into());
+    }
+    if unshield_info.inst[0] != UNSHIELD_TO_PDA_META_TYPE {
+        msg!("Invalid meta type {} for unshield and call", unshield_info.inst[0]);
+        return Err(BridgeError::InvalidMetaType.into());
+    }
+    let incognito_proxy = dapp_accounts
+        .get(dapp_request.num_acc as usize + 2)
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    if incognito_proxy.key != unshield_accounts[4].key {
+        msg!("Dapp call and unshield use different incognito proxies");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    process_unshield(unshield_accounts, unshield_info, program_id)?;
+    process_dapp_interaction(dapp_accounts, dapp_request, program_id)
+}
+
+// store the instruction root of a beacon block signed by the committee
+fn process_verify_beacon_block(
+    accounts: &[AccountInfo],
+    block_info: BeaconBlockRequest,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    let verified_root = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    assert_writable(verified_root)?;
+    assert_signer(payer)?;
+    assert_writable(payer)?;
+    let incognito_proxy_info = assert_incognito_proxy(incognito_proxy, program_id)?;
+
+    // keyed by the instruction root, the beacon height is bound by the instruction leaves
+    let bump = assert_pda(
+        verified_root,
+        &[VERIFIED_ROOT_SEED, incognito_proxy.key.as_ref(), &block_info.inst_root],
+        program_id,
+        BridgeError::InvalidVerifiedRootAccount,
+    )?;
+    if verified_root.owner == program_id {
+        msg!("Beacon block already verified");
+        return Err(BridgeError::BeaconBlockAlreadyVerified.into());
+    }
+
+    quorum::verify_beacon_signatures(
+        &incognito_proxy_info.beacons,
+        incognito_proxy_info.quorum_threshold,
+        &block_info.blk_data,
+        &block_info.inst_root,
+        &block_info.indexes,
+        &block_info.signatures,
+    )?;
+
+    _create_pda_account(
+        verified_root,
+        payer,
+        system_program,
+        program_id,
+        &[VERIFIED_ROOT_SEED, incognito_proxy.key.as_ref(), &block_info.inst_root, &[bump]],
+        VERIFIED_ROOT_LEN,
+    )?;
+    verified_root.data.borrow_mut().copy_from_slice(&block_info.inst_root);
+
+    Ok(())
+}
+
+// set the authority of a buffer holding a large unshield proof
+fn process_init_proof_buffer(
+    accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let rent = &Rent::from_account_info(next_account_info(account_info_iter)?)?;
+    let proof_buffer = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    assert_signer(authority)?;
+    assert_writable(proof_buffer)?;
+    assert_owned_by(proof_buffer, program_id)?;
+    assert_rent_exempt(rent, proof_buffer)?;
+    if proof_buffer.data_len() <= ProofBuffer::LEN {
+        msg!("Proof buffer too small {}", proof_buffer.data_len());
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    let mut proof_buffer_data = proof_buffer.data.borrow_mut();
+    let (header, proof) = proof_buffer_data.split_at_mut(ProofBuffer::LEN);
+    if ProofBuffer::unpack_unchecked(header)?.is_initialized() {
+        return Err(BridgeError::AccInitialized.into());
+    }
+    // only accounts freshly created for the buffer, other accounts of the program hold data
+    if header.iter().chain(proof.iter()).any(|byte| *byte != 0) {
+        msg!("Proof buffer is not zeroed");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    ProofBuffer::pack(
+        ProofBuffer {
+            is_initialized: true,
+            authority: *authority.key,
+        },
+        header,
+    )
+}
+
+// copy a chunk of the unshield proof into the buffer
+fn process_write_proof_chunk(
+    accounts: &[AccountInfo],
+    offset: u32,
+    data: Vec<u8>,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let proof_buffer = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    _verify_proof_buffer(proof_buffer, authority, program_id)?;
+
+    let mut proof_buffer_data = proof_buffer.data.borrow_mut();
+    let proof = &mut proof_buffer_data[ProofBuffer::LEN..];
+    let start = offset as usize;
+    let end = start.saturating_add(data.len());
+    if end > proof.len() {
+        msg!("Chunk {}..{} exceeds proof length {}", start, end, proof.len());
+        return Err(BridgeError::ProofChunkOutOfBounds.into());
+    }
+    proof[start..end].copy_from_slice(&data);
+
+    Ok(())
+}
+
+// unshield with the uploaded proof, the buffer rent goes back to its authority
+fn process_unshield_from_buffer(
+    accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> ProgramResult {
+    if accounts.len() < 2 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let (buffer_accounts, unshield_accounts) = accounts.split_at(2);
+    let proof_buffer = &buffer_accounts[0];
+    let authority = &buffer_accounts[1];
+    assert_writable(authority)?;
+    _verify_proof_buffer(proof_buffer, authority, program_id)?;
+
+    let unshield_info = BridgeInstruction::unpack_unshield_proof(&proof_buffer.data.borrow()[ProofBuffer::LEN..])?;
+    process_unshield(unshield_accounts, unshield_info, program_id)?;
+
+    _close_proof_buffer(proof_buffer, authority)
+}
+
+// close a proof buffer left unused, e.g. after a failed upload
+fn process_close_proof_buffer(
+    accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let proof_buffer = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    assert_writable(authority)?;
+    _verify_proof_buffer(proof_buffer, authority, program_id)?;
+
+    _close_proof_buffer(proof_buffer, authority)
+}
+
+// create the allowlist of the program, bound to the incognito proxy holding user funds
+fn process_init_allowlist(
+    accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let allowlist = next_account_info(account_info_iter)?;
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    let upgrade_authority = next_account_info(account_info_iter)?;
+    let program_data = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    assert_writable(allowlist)?;
+    assert_signer(payer)?;
+    assert_writable(payer)?;
+    assert_upgrade_authority(program_data, upgrade_authority, program_id)?;
+    assert_incognito_proxy(incognito_proxy, program_id)?;
+    let bump = assert_pda(allowlist, &[ALLOWLIST_SEED], program_id, BridgeError::InvalidAllowlistAccount)?;
+    if allowlist.owner == program_id {
+        msg!("Allowlist already initialized");
+        return Err(BridgeError::AccInitialized.into());
+    }
+
+    _create_pda_account(
+        allowlist,
+        payer,
+        system_program,
+        program_id,
+        &[ALLOWLIST_SEED, &[bump]],
+        DappAllowlist::LEN,
+    )?;
+    DappAllowlist::pack(
+        DappAllowlist {
+            is_initialized: true,
+            incognito_proxy: *incognito_proxy.key,
+            programs: vec![],
+        },
+        &mut allowlist.data.borrow_mut(),
+    )?;
+
+    Ok(())
+}
+
+// approve a destination program for dapp interaction
+fn process_add_allowed_program(
+    accounts: &[AccountInfo],
+    program: Pubkey,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let allowlist = next_account_info(account_info_iter)?;
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    let guardian = next_account_info(account_info_iter)?;
+    assert_writable(allowlist)?;
+    _verify_guardian(incognito_proxy, guardian, program_id)?;
+
+    let mut allowlist_info = _verify_allowlist(allowlist, incognito_proxy.key, program_id)?;
+    if allowlist_info.programs.contains(&program) {
+        return Err(BridgeError::ProgramAlreadyAllowed.into());
+    }
+    if allowlist_info.programs.len() >= MAX_ALLOWED_PROGRAMS {
+        return Err(BridgeError::AllowlistFull.into());
+    }
+    allowlist_info.programs.push(program);
+    DappAllowlist::pack(allowlist_info, &mut allowlist.data.borrow_mut())?;
+    BridgeEvent::DappProgramAdded {
+        incognito_proxy: *incognito_proxy.key,
+        program,
+    }.emit();
+
+    Ok(())
+}
+
+// revoke a destination program for dapp interaction
+fn process_remove_allowed_program(
+    accounts: &[AccountInfo],
+    program: Pubkey,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let allowlist = next_account_info(account_info_iter)?;
+    let incognito_proxy = next_account_info(account_info_iter)?;
+    let guardian = next_account_info(account_info_iter)?;
+    assert_writable(allowlist)?;
+    _verify_guardian(incognito_proxy, guardian, program_id)?;
+
+    let mut allowlist_info = _verify_allowlist(allowlist, incognito_proxy.key, program_id)?;
+    let position = allowlist_info.programs
+        .iter()
+        .position(|allowed| *allowed == program)
+        .ok_or(BridgeError::ProgramNotAllowed)?;
+    allowlist_info.programs.remove(position);
+    DappAllowlist::pack(allowlist_info, &mut allowlist.data.borrow_mut())?;
+    BridgeEvent::DappProgramRemoved {
+        incognito_proxy: *incognito_proxy.key,
+        program,
+    }.emit();
 
     Ok(())
 }
@@ -454,43 +1388,207 @@ fn _process_init_map(vault: &AccountInfo) -> ProgramResult {
     Ok(())
 }
 
-fn _process_insert_entry(vault: &AccountInfo, program_id: &Pubkey, txid: &[u8; 32]) -> ProgramResult {
-    if vault.data.borrow()[0] == 0 || *vault.owner != *program_id {
+// tx ids burnt before replay protection moved to one account per tx id
+fn _verify_legacy_entry(vault: &AccountInfo, program_id: &Pubkey, txid: &[u8; 32]) -> ProgramResult {
+    assert_owned_by(vault, program_id)?;
+    if vault.data.borrow()[0] == 0 {
         return Err(BridgeError::InvalidMapAccount.into())
     }
-    let mut map_state = try_from_slice_unchecked::<Vault>(&vault.data.borrow())?;
+    let map_state = try_from_slice_unchecked::<Vault>(&vault.data.borrow())?;
 
     if map_state.map.contains_key(txid) {
         return Err(BridgeError::InvalidUnshieldRequestUsed.into())
     }
 
-    map_state.map.insert(*txid, true);
-    map_state.serialize(&mut &mut vault.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// mark tx id as used by creating its burn tx account
+fn _process_insert_entry<'a>(
+    burn_tx_account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    incognito_proxy: &Pubkey,
+    program_id: &Pubkey,
+    txid: &[u8; 32],
+) -> ProgramResult {
+    let bump = assert_pda(
+        burn_tx_account,
+        &[BURN_TX_SEED, incognito_proxy.as_ref(), txid],
+        program_id,
+        BridgeError::InvalidMapAccount,
+    )?;
+    if burn_tx_account.owner == program_id {
+        return Err(BridgeError::InvalidUnshieldRequestUsed.into())
+    }
+
+    _create_pda_account(
+        burn_tx_account,
+        payer,
+        system_program,
+        program_id,
+        &[BURN_TX_SEED, incognito_proxy.as_ref(), txid, &[bump]],
+        BURN_TX_LEN,
+    )?;
+    burn_tx_account.data.borrow_mut()[0] = 1;
 
     Ok(())
 }
 
-fn _verify_vault_token_account(incognito_proxy: AccountInfo, vault_token_account: AccountInfo, program_id: Pubkey) -> Result<Pubkey, ProgramError> {
-    let vault_token_account_info = TokenAccount::unpack(&vault_token_account.try_borrow_data()?)?;
-    let incognito_proxy_info = IncognitoProxy::unpack(&incognito_proxy.try_borrow_data()?)?;
-    let authority_signer_seeds = &[
-        incognito_proxy.key.as_ref(),
-        &[incognito_proxy_info.bump_seed],
-    ];
-    let vault_authority_pubkey =
-        Pubkey::create_program_address(authority_signer_seeds, &program_id)?;
+// create a program owned account at a pda, signer seeds include the bump
+fn _create_pda_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    program_id: &Pubkey,
+    signer_seeds: &[&[u8]],
+    space: usize,
+) -> ProgramResult {
+    assert_system_program(system_program)?;
+    let rent = Rent::get()?;
+    let required_lamports = rent
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    // anyone can transfer lamports to the address in advance, so create it in steps
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required_lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, program_id),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )?;
 
-    let incognio_proxy_associated_acc = get_associated_token_address(
-        &vault_authority_pubkey,
-        &vault_token_account_info.mint
-    );
+    Ok(())
+}
+
+fn _verify_guardian(incognito_proxy: &AccountInfo, guardian: &AccountInfo, program_id: &Pubkey) -> Result<IncognitoProxy, ProgramError> {
+    let incognito_proxy_info = assert_incognito_proxy(incognito_proxy, program_id)?;
+    if !guardian.is_signer || incognito_proxy_info.guardian != *guardian.key {
+        msg!("Only guardian can perform this action");
+        return Err(BridgeError::InvalidGuardian.into());
+    }
+    Ok(incognito_proxy_info)
+}
+
+// beacon instruction of one of `meta_types`, other meta types are rejected before their payload is decoded
+fn _decode_beacon_inst(inst: &[u8], meta_types: &[u8]) -> Result<BeaconInstruction, ProgramError> {
+    match inst.first() {
+        Some(meta_type) if meta_types.contains(meta_type) => BeaconInstruction::decode(inst),
+        _ => {
+            msg!("Invalid beacon instruction metatype {:?}", inst.first());
+            Err(BridgeError::InvalidMetaType.into())
+        }
+    }
+}
+
+// instruction root stored by verify beacon block
+fn _verify_root_stored(verified_root: &AccountInfo, incognito_proxy: &Pubkey, inst_root: &[u8; 32], program_id: &Pubkey) -> ProgramResult {
+    assert_pda(
+        verified_root,
+        &[VERIFIED_ROOT_SEED, incognito_proxy.as_ref(), inst_root],
+        program_id,
+        BridgeError::InvalidVerifiedRootAccount,
+    )?;
+    if verified_root.owner != program_id || verified_root.data.borrow()[..] != inst_root[..] {
+        msg!("Beacon block not verified");
+        return Err(BridgeError::BeaconBlockNotVerified.into());
+    }
+    Ok(())
+}
 
-    if incognio_proxy_associated_acc != *vault_token_account.key {
-        msg!("Only incognito proxy account will be accepted");
-        return Err(ProgramError::IncorrectProgramId);
+fn _verify_proof_buffer(proof_buffer: &AccountInfo, authority: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+    assert_writable(proof_buffer)?;
+    assert_signer(authority)?;
+    assert_owned_by(proof_buffer, program_id)?;
+    let proof_buffer_data = proof_buffer.data.borrow();
+    if proof_buffer_data.len() < ProofBuffer::LEN {
+        return Err(ProgramError::AccountDataTooSmall);
     }
+    let proof_buffer_info = ProofBuffer::unpack(&proof_buffer_data[..ProofBuffer::LEN])?;
+    if proof_buffer_info.authority != *authority.key {
+        msg!("Proof buffer authority and key provided not match {}, {}", proof_buffer_info.authority, authority.key);
+        return Err(BridgeError::InvalidProofBufferAuthority.into());
+    }
+    Ok(())
+}
+
+// drain the proof buffer rent to its authority and clear the data so it cannot be used again
+fn _close_proof_buffer(proof_buffer: &AccountInfo, authority: &AccountInfo) -> ProgramResult {
+    let authority_lamports = authority
+        .lamports()
+        .checked_add(proof_buffer.lamports())
+        .ok_or(ProgramError::InvalidArgument)?;
+    **authority.lamports.borrow_mut() = authority_lamports;
+    **proof_buffer.lamports.borrow_mut() = 0;
+    proof_buffer.data.borrow_mut().fill(0);
+
+    Ok(())
+}
+
+fn _verify_program_allowed(allowlist_info: &DappAllowlist, program: &Pubkey) -> ProgramResult {
+    if !allowlist_info.programs.contains(program) {
+        msg!("Program {} not allowed", program);
+        return Err(BridgeError::ProgramNotAllowed.into());
+    }
+    Ok(())
+}
 
-    Ok((vault_token_account_info.mint))
+fn _token_balance(accounts_info: &[AccountInfo], account_index: u16) -> Result<u64, ProgramError> {
+    let token_account = accounts_info
+        .get(account_index as usize)
+        .ok_or(BridgeError::InvalidDappCall)?;
+    Ok(assert_token_account(token_account)?.amount)
+}
+
+// call a dapp as the signer authority
+fn _invoke_dapp(new_inst: &Instruction, accounts_info: &[AccountInfo], authority_signer_seeds: &[&[u8]]) -> ProgramResult {
+    invoke_signed(
+        new_inst,
+        accounts_info,
+        &[authority_signer_seeds],
+    ).map_err(|err| {
+        msg!("Dapp interaction with {} failed: {}", new_inst.program_id, err);
+        dapp_error(err)
+    })?;
+    // forward swap outputs of the called program to the caller
+    if let Some((program, data)) = get_return_data() {
+        if program == new_inst.program_id {
+            set_return_data(&data);
+        }
+    }
+    Ok(())
+}
+
+// the allowlist of the program, only valid with the incognito proxy it is bound to
+fn _verify_allowlist(allowlist: &AccountInfo, incognito_proxy: &Pubkey, program_id: &Pubkey) -> Result<DappAllowlist, ProgramError> {
+    assert_pda(allowlist, &[ALLOWLIST_SEED], program_id, BridgeError::InvalidAllowlistAccount)?;
+    if allowlist.owner != program_id {
+        msg!("Invalid allowlist account owner");
+        return Err(BridgeError::InvalidAllowlistAccount.into());
+    }
+    let allowlist_info = DappAllowlist::unpack(&allowlist.try_borrow_data()?)?;
+    if allowlist_info.incognito_proxy != *incognito_proxy {
+        msg!("Allowlist not belong to incognito proxy");
+        return Err(BridgeError::InvalidAllowlistAccount.into());
+    }
+    Ok(allowlist_info)
+}
+
+fn _verify_not_paused(incognito_proxy_info: &IncognitoProxy) -> ProgramResult {
+    if incognito_proxy_info.is_paused {
+        msg!("Bridge is paused");
+        return Err(BridgeError::BridgePaused.into());
+    }
+    Ok(())
 }
 
 // check rent exempt
@@ -587,52 +1685,3 @@ struct TokenCloseParams<'a: 'b, 'b> {
     authority_signer_seeds: &'b [&'b [u8]],
     token_program: AccountInfo<'a>,
 }
-
-fn assert_uninitialized<T: Pack + IsInitialized>(
-    account_info: &AccountInfo,
-) -> Result<T, ProgramError> {
-    let account: T = T::unpack_unchecked(&account_info.data.borrow())?;
-    if account.is_initialized() {
-        Err(BridgeError::AccInitialized.into())
-    } else {
-        Ok(account)
-    }
-}
-
-fn instruction_in_merkle_tree(
-    leaf: &[u8; 32],
-    root: &[u8; 32],
-    paths: &Vec<[u8; 32]>,
-    path_lefts: &Vec<bool>
-) -> bool {
-    if paths.len() != path_lefts.len() {
-        msg!("paths and path_lefts is not match");
-        return false;
-    }
-    let mut build_root = leaf.clone();
-    for i in 0..paths.len() {
-        if path_lefts[i] {
-            let mut temp = paths[i][..].to_vec();
-            temp.extend_from_slice(&build_root[..]);
-            build_root = hash(&temp[..]).to_bytes();
-        } else if paths[i] == [0; 32] {
-            let mut temp = build_root[..].to_vec();
-            temp.extend_from_slice(&build_root[..]);
-            build_root = hash(&temp[..]).to_bytes();
-        } else {
-            let mut temp = build_root[..].to_vec();
-            temp.extend_from_slice(&paths[i][..]);
-            build_root = hash(&temp[..]).to_bytes();
-        }
-    }
-    build_root == *root
-}
-
-fn append_at_top(input: u64) -> Vec<u8>  {
-    let mut  input_vec = input.to_be_bytes().to_vec();
-    for _ in 0..24 {
-        input_vec.insert(0, 0);
-    }
-
-    input_vec
-}
\ No newline at end of file
@@ -0,0 +1,125 @@
+//! Merkle proofs of beacon instructions, shared by the program and off-chain relayers.
+
+use solana_program::{keccak::hash, msg};
+
+/// Merkle path proving a beacon instruction is included in the instruction root of a beacon block.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InstructionProof {
+    // inst paths to build merkle tree, zero when the node is hashed with itself
+    pub inst_paths: Vec<[u8; 32]>,
+    // inst path indicator
+    pub inst_path_is_lefts: Vec<bool>,
+}
+
+impl InstructionProof {
+    /// Builds the proof of the leaf at `index` in the merkle tree of `leaves`, as Incognito builds it.
+    ///
+    /// Each level is padded to a power of two, a node without right sibling is hashed with itself
+    /// and its path is zero. Returns `None` if `index` is out of range.
+    pub fn build(leaves: &[[u8; 32]], index: usize) -> Option<Self> {
+        if index >= leaves.len() {
+            return None;
+        }
+        let mut proof = Self::default();
+        let mut level = leaves.to_vec();
+        let mut index = index;
+        while level.len() > 1 {
+            let sibling = index ^ 1;
+            proof.inst_paths.push(level.get(sibling).copied().unwrap_or([0; 32]));
+            proof.inst_path_is_lefts.push(sibling < index);
+            level = parent_level(&level);
+            index /= 2;
+        }
+        Some(proof)
+    }
+
+    /// Computes the root from `leaf` up along the path, `None` if paths and is lefts lengths differ.
+    pub fn root(&self, leaf: &[u8; 32]) -> Option<[u8; 32]> {
+        path_root(leaf, &self.inst_paths, &self.inst_path_is_lefts)
+    }
+
+    /// Checks that `leaf` is included in the tree of `root`.
+    pub fn verify(&self, leaf: &[u8; 32], root: &[u8; 32]) -> bool {
+        instruction_in_merkle_tree(leaf, root, &self.inst_paths, &self.inst_path_is_lefts)
+    }
+}
+
+/// Root of the merkle tree of `leaves` as built by Incognito, zero for an empty tree.
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = parent_level(&level);
+    }
+    level.first().copied().unwrap_or([0; 32])
+}
+
+/// Checks that `leaf` is included in the tree of `root` along `paths`.
+pub fn instruction_in_merkle_tree(
+    leaf: &[u8; 32],
+    root: &[u8; 32],
+    paths: &[[u8; 32]],
+    path_lefts: &[bool],
+) -> bool {
+    match path_root(leaf, paths, path_lefts) {
+        Some(build_root) => build_root == *root,
+        None => {
+            msg!("paths and path_lefts is not match");
+            false
+        }
+    }
+}
+
+/// Hash of beacon instruction with block height appended, the leaf of instruction merkle tree.
+pub fn instruction_hash(inst: &[u8], height: u64) -> [u8; 32] {
+    let mut inst_vec = inst.to_vec();
+    inst_vec.extend_from_slice(&append_at_top(height));
+    hash(&inst_vec).to_bytes()
+}
+
+/// Big endian 256 bit encoding of `input`, as numbers are encoded in beacon instructions.
+pub fn append_at_top(input: u64) -> [u8; 32] {
+    let mut input_vec = [0; 32];
+    input_vec[24..].copy_from_slice(&input.to_be_bytes());
+    input_vec
+}
+
+/// Hash of a beacon block signed by the beacon committee, the double keccak hash of `blk_data` and `inst_root`.
+pub fn beacon_block_hash(blk_data: &[u8; 32], inst_root: &[u8; 32]) -> [u8; 32] {
+    hash(&beacon_block_message(blk_data, inst_root)).to_bytes()
+}
+
+/// Beacon block hash before its last keccak hash, the message checked by the Secp256k1 program.
+pub fn beacon_block_message(blk_data: &[u8; 32], inst_root: &[u8; 32]) -> [u8; 32] {
+    hash_pair(blk_data, inst_root)
+}
+
+fn path_root(leaf: &[u8; 32], paths: &[[u8; 32]], path_lefts: &[bool]) -> Option<[u8; 32]> {
+    if paths.len() != path_lefts.len() {
+        return None;
+    }
+    let mut build_root = *leaf;
+    for (path, is_left) in paths.iter().zip(path_lefts) {
+        build_root = if *is_left {
+            hash_pair(path, &build_root)
+        } else if *path == [0; 32] {
+            hash_pair(&build_root, &build_root)
+        } else {
+            hash_pair(&build_root, path)
+        };
+    }
+    Some(build_root)
+}
+
+// parents of a level padded to a power of two, a node without right sibling is hashed with itself
+fn parent_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+        .collect()
+}
+
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut temp = left.to_vec();
+    temp.extend_from_slice(right);
+    hash(&temp).to_bytes()
+}
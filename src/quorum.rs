@@ -0,0 +1,160 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    keccak::hash,
+    msg,
+    secp256k1_program,
+    secp256k1_recover::{secp256k1_recover, Secp256k1Pubkey},
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+use arrayref::array_ref;
+use crate::{
+    error::BridgeError,
+    proof::{beacon_block_hash, beacon_block_message},
+    state::QuorumThreshold,
+};
+
+/// Verifies that more than `threshold` of the beacon committee signed a beacon block.
+///
+/// Signed data is the double keccak hash of `blk_data` and `inst_root`. `indexes` must be
+/// strictly ascending so every beacon counts at most once toward the quorum.
+pub fn verify_beacon_signatures(
+    beacons: &[Secp256k1Pubkey],
+    threshold: QuorumThreshold,
+    blk_data: &[u8; 32],
+    inst_root: &[u8; 32],
+    indexes: &[u8],
+    signatures: &[[u8; 65]],
+) -> ProgramResult {
+    if indexes.len() != signatures.len() {
+        msg!("Invalid instruction provided, length of indexes and signatures not match");
+        return Err(BridgeError::InvalidBeaconInstruction.into());
+    }
+
+    verify_quorum(signatures.len(), beacons.len(), threshold)?;
+    verify_indexes(indexes, beacons.len())?;
+
+    // Get double block hash from instRoot and other data
+    let blk = beacon_block_hash(blk_data, inst_root);
+
+    for (index_beacon, s_r_v) in indexes.iter().zip(signatures) {
+        let (s_r, v) = s_r_v.split_at(64);
+        let beacon_key_from_signature = secp256k1_recover(
+            &blk[..],
+            v[0],
+            s_r,
+        ).map_err(|_| {
+            msg!("Signature of beacon {} cannot be recovered", index_beacon);
+            BridgeError::InvalidBeaconSignature
+        })?;
+        if beacon_key_from_signature != beacons[*index_beacon as usize] {
+            msg!("Invalid signature of beacon {}", index_beacon);
+            return Err(BridgeError::InvalidBeaconSignature.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Size of the signature offsets of each signature in a Secp256k1 program instruction.
+pub const SECP256K1_SIGNATURE_OFFSETS_LEN: usize = 11;
+
+/// Verifies that more than `threshold` of the beacon committee signed a beacon block, with the
+/// signatures checked by the Secp256k1 program instruction right before the current instruction.
+///
+/// The Secp256k1 program hashes the message once more, so each signature must sign the keccak hash
+/// of `blk_data` and `inst_root` for the eth address of the beacon at the same position in `indexes`.
+/// All offsets must point into the Secp256k1 instruction itself.
+pub fn verify_secp256k1_signatures(
+    beacons: &[Secp256k1Pubkey],
+    threshold: QuorumThreshold,
+    blk_data: &[u8; 32],
+    inst_root: &[u8; 32],
+    indexes: &[u8],
+    instructions_sysvar: &AccountInfo,
+) -> ProgramResult {
+    verify_quorum(indexes.len(), beacons.len(), threshold)?;
+    verify_indexes(indexes, beacons.len())?;
+
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let secp256k1_index = current_index.checked_sub(1).ok_or_else(|| {
+        msg!("Secp256k1 instruction must precede instruction {}", current_index);
+        BridgeError::MissingSecp256k1Instruction
+    })?;
+    let secp256k1_instruction = load_instruction_at_checked(secp256k1_index as usize, instructions_sysvar)?;
+    if secp256k1_instruction.program_id != secp256k1_program::id() {
+        msg!("Instruction {} is not a secp256k1 instruction", secp256k1_index);
+        return Err(BridgeError::MissingSecp256k1Instruction.into());
+    }
+
+    let data = &secp256k1_instruction.data;
+    let num_signatures = data.first().copied().unwrap_or(0) as usize;
+    if num_signatures != indexes.len() || data.len() < 1 + num_signatures * SECP256K1_SIGNATURE_OFFSETS_LEN {
+        msg!("Secp256k1 instruction checks {} signatures, expected {}", num_signatures, indexes.len());
+        return Err(BridgeError::InvalidSecp256k1Instruction.into());
+    }
+
+    let message = beacon_block_message(blk_data, inst_root);
+    for (i, index_beacon) in indexes.iter().enumerate() {
+        let offsets = array_ref![data, 1 + i * SECP256K1_SIGNATURE_OFFSETS_LEN, SECP256K1_SIGNATURE_OFFSETS_LEN];
+        let eth_address_offset = u16::from_le_bytes([offsets[3], offsets[4]]) as usize;
+        let message_offset = u16::from_le_bytes([offsets[6], offsets[7]]) as usize;
+        let message_size = u16::from_le_bytes([offsets[8], offsets[9]]) as usize;
+        let instruction_indexes = [offsets[2], offsets[5], offsets[10]];
+        if instruction_indexes.iter().any(|index| *index as u16 != secp256k1_index) {
+            msg!("Signature {} of the secp256k1 instruction refers to another instruction", i);
+            return Err(BridgeError::InvalidSecp256k1Instruction.into());
+        }
+        let signed_message = data.get(message_offset..message_offset.saturating_add(message_size));
+        if message_size != message.len() || signed_message != Some(&message[..]) {
+            msg!("Signature {} of the secp256k1 instruction does not sign the beacon block", i);
+            return Err(BridgeError::InvalidSecp256k1Instruction.into());
+        }
+        let eth_address = data.get(eth_address_offset..eth_address_offset.saturating_add(20));
+        if eth_address != Some(&beacon_eth_address(&beacons[*index_beacon as usize])[..]) {
+            msg!("Signature {} of the secp256k1 instruction is not from beacon {}", i, index_beacon);
+            return Err(BridgeError::InvalidBeaconSignature.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Ethereum address of a beacon, the last 20 bytes of the keccak hash of its public key.
+pub fn beacon_eth_address(beacon: &Secp256k1Pubkey) -> [u8; 20] {
+    let beacon_hash = hash(&beacon.to_bytes()).to_bytes();
+    *array_ref![beacon_hash, 12, 20]
+}
+
+// more than `threshold` of the beacons must sign
+fn verify_quorum(num_signatures: usize, num_beacons: usize, threshold: QuorumThreshold) -> ProgramResult {
+    if num_signatures * threshold.denominator as usize <= num_beacons * threshold.numerator as usize {
+        msg!("Not enough signatures {} for {} beacons", num_signatures, num_beacons);
+        return Err(BridgeError::InvalidNumberOfSignature.into());
+    }
+    Ok(())
+}
+
+// indexes must be strictly ascending and refer to a beacon in the committee
+fn verify_indexes(indexes: &[u8], num_beacons: usize) -> ProgramResult {
+    for (i, index) in indexes.iter().enumerate() {
+        if *index as usize >= num_beacons {
+            msg!("Beacon index {} out of range {}", index, num_beacons);
+            return Err(BridgeError::BeaconIndexOutOfRange.into());
+        }
+        if i == 0 {
+            continue;
+        }
+        let prev = indexes[i - 1];
+        if *index == prev {
+            msg!("Duplicate beacon index {}", index);
+            return Err(BridgeError::DuplicateBeaconIndex.into());
+        }
+        if *index < prev {
+            msg!("Beacon index {} after {}", index, prev);
+            return Err(BridgeError::UnsortedBeaconIndexes.into());
+        }
+    }
+
+    Ok(())
+}
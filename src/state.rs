@@ -1,15 +1,19 @@
 use solana_program::{
+    msg,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::{Pubkey, PUBKEY_BYTES},
     secp256k1_recover::{Secp256k1Pubkey, SECP256K1_PUBLIC_KEY_LENGTH},
 };
-use std::{collections::BTreeMap};
+use std::{collections::BTreeMap, fmt};
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use borsh::{BorshSerialize, BorshDeserialize};
 use crate::error::BridgeError;
 
 /// ====== INCOGNITO VAULT =======
+///
+/// Burnt tx ids of the first unshields, kept read only to reject replays.
+/// Newer tx ids are stored in one account per tx id.
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
 pub struct Vault {
     pub is_initialized: u8,
@@ -20,10 +24,56 @@ impl Vault {
     const LEN: usize = 1 + (4 + (100 * 33)); // 100 tx id to store
 }
 
+/// ====== BURN TX =======
+///
+/// Seed of the account created for each unshielded burn tx id,
+/// derived from `find_program_address(&[BURN_TX_SEED, incognito proxy, tx id])`
+pub const BURN_TX_SEED: &[u8] = b"burn_tx";
+/// Burn tx account stores a single used flag
+pub const BURN_TX_LEN: usize = 1;
+
+/// ====== VERIFIED BEACON BLOCK =======
+///
+/// Seed of the account storing the instruction root of a beacon block signed by the committee,
+/// derived from `find_program_address(&[VERIFIED_ROOT_SEED, incognito proxy, instruction root])`
+pub const VERIFIED_ROOT_SEED: &[u8] = b"verified_root";
+/// Verified root account stores the instruction root
+pub const VERIFIED_ROOT_LEN: usize = 32;
+
 /// ====== INCOGNITO PROXY =======
 /// 
-/// Max number of beacon addresses
-pub const MAX_BEACON_ADDRESSES: usize = 20;
+/// Max number of beacon addresses, beacons are counted and indexed by a u8
+pub const MAX_BEACON_ADDRESSES: usize = 255;
+/// Beacon capacity of proxy accounts created by clients
+pub const DEFAULT_BEACON_CAPACITY: usize = 32;
+/// Layout version of the incognito proxy account
+pub const PROXY_VERSION: u8 = 1;
+/// Layout version of incognito proxy accounts created before the layout was versioned
+pub const LEGACY_PROXY_VERSION: u8 = 0;
+
+/// Share of the beacon committee that must sign, more than `numerator / denominator`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuorumThreshold {
+    pub numerator: u8,
+    pub denominator: u8,
+}
+
+impl Default for QuorumThreshold {
+    fn default() -> Self {
+        QuorumThreshold {
+            numerator: 2,
+            denominator: 3,
+        }
+    }
+}
+
+impl QuorumThreshold {
+    /// Threshold must be at least 1/2, so more than half of the committee signs,
+    /// and below 1, so the full committee can reach it
+    pub fn is_valid(&self) -> bool {
+        self.numerator < self.denominator && 2 * self.numerator as u16 >= self.denominator as u16
+    }
+}
 
 // Incognito proxy stores beacon list
 #[derive(Clone, Default, PartialEq)]
@@ -34,10 +84,37 @@ pub struct IncognitoProxy {
     pub bump_seed: u8,
     // vault key
     pub vault: Pubkey,
+    // beacon height from which the beacon list is valid
+    pub start_height: u64,
+    // guardian allowed to pause the bridge
+    pub guardian: Pubkey,
+    // shield, unshield and dapp interaction disabled
+    pub is_paused: bool,
+    // share of the beacon committee that must sign
+    pub quorum_threshold: QuorumThreshold,
+    // beacon height of the last quorum threshold update
+    pub threshold_height: u64,
     /// beacon list
     pub beacons: Vec<Secp256k1Pubkey>, 
 }
 
+// Secp256k1Pubkey does not implement Debug, print beacons as raw bytes
+impl fmt::Debug for IncognitoProxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IncognitoProxy")
+            .field("is_initialized", &self.is_initialized)
+            .field("bump_seed", &self.bump_seed)
+            .field("vault", &self.vault)
+            .field("start_height", &self.start_height)
+            .field("guardian", &self.guardian)
+            .field("is_paused", &self.is_paused)
+            .field("quorum_threshold", &self.quorum_threshold)
+            .field("threshold_height", &self.threshold_height)
+            .field("beacons", &self.beacons.iter().map(|beacon| beacon.0).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
 impl IsInitialized for IncognitoProxy {
     fn is_initialized(&self) -> bool {
         self.is_initialized
@@ -57,99 +134,429 @@ impl IncognitoProxy {
         self.is_initialized = params.is_initialized;
         self.bump_seed = params.bump_seed;
         self.vault = params.vault;
+        self.start_height = params.start_height;
+        self.guardian = params.guardian;
+        self.is_paused = params.is_paused;
+        self.quorum_threshold = params.quorum_threshold;
+        self.threshold_height = params.threshold_height;
         self.beacons = params.beacons;
     }
-}
 
-impl Sealed for IncognitoProxy {}
+    /// 1 + 1 + 1 + 32 + 8 + 32 + 1 + 1 + 1 + 8 + 1, followed by 64 bytes per beacon
+    pub const HEADER_LEN: usize = 87;
 
-impl Pack for IncognitoProxy {
-    /// 1 + 1 + 32 + 1 + 64 * 20
-    const LEN: usize = 1315;
-    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let src = array_ref![src, 0, IncognitoProxy::LEN];
+    /// 1 + 1 + 32 + 1 + 64 * 20, size of unversioned accounts which hold no version byte
+    pub const LEGACY_LEN: usize = 1315;
+
+    /// Number of beacons unversioned accounts hold
+    pub const LEGACY_CAPACITY: usize = 20;
+
+    /// Account size holding up to `capacity` beacons
+    pub const fn space(capacity: usize) -> usize {
+        Self::HEADER_LEN + SECP256K1_PUBLIC_KEY_LENGTH * capacity
+    }
+
+    /// Number of beacons an account of `data_len` bytes can hold
+    pub fn capacity(data_len: usize) -> usize {
+        data_len.saturating_sub(Self::HEADER_LEN) / SECP256K1_PUBLIC_KEY_LENGTH
+    }
+
+    /// Unpacks an initialized incognito proxy
+    pub fn unpack(src: &[u8]) -> Result<Self, ProgramError> {
+        let incognito_proxy = Self::unpack_unchecked(src)?;
+        if !incognito_proxy.is_initialized {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        Ok(incognito_proxy)
+    }
+
+    /// Layout version of the account, unversioned accounts are recognized by their size
+    pub fn version(src: &[u8]) -> Result<u8, ProgramError> {
+        match src {
+            _ if src.len() == Self::LEGACY_LEN => Ok(LEGACY_PROXY_VERSION),
+            [_, version, ..] if src.len() >= Self::HEADER_LEN => Ok(*version),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Unpacks an incognito proxy of any capacity without checking it is initialized.
+    /// Unversioned accounts are unpacked with the defaults of the fields they lack.
+    pub fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() == Self::LEGACY_LEN {
+            return Self::unpack_legacy(src);
+        }
+        if src.len() < Self::HEADER_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (header, data_flat) = src.split_at(Self::HEADER_LEN);
+        let header = array_ref![header, 0, IncognitoProxy::HEADER_LEN];
+        #[allow(clippy::ptr_offset_with_cast)]
         let (
             is_initialized,
+            version,
             bump_seed,
             vault_key,
+            start_height,
+            guardian,
+            is_paused,
+            quorum_numerator,
+            quorum_denominator,
+            threshold_height,
             beacon_len,
-            data_flat
         ) = array_refs![
-            src, 
+            header,
+            1,
             1,
-            1, 
-            PUBKEY_BYTES, 
-            1, 
-            SECP256K1_PUBLIC_KEY_LENGTH * MAX_BEACON_ADDRESSES
+            1,
+            PUBKEY_BYTES,
+            8,
+            PUBKEY_BYTES,
+            1,
+            1,
+            1,
+            8,
+            1
         ];
-        let is_initialized = match is_initialized {
-            [0] => false,
-            [1] => true,
-            _ => return Err(BridgeError::InvalidBoolValue.into()),
-        };
+        let is_initialized = unpack_bool(is_initialized)?;
+        let is_paused = unpack_bool(is_paused)?;
+        if is_initialized && version[0] != PROXY_VERSION {
+            return Err(BridgeError::UnsupportedProxyVersion.into());
+        }
 
-        let beacon_len = u8::from_le_bytes(*beacon_len);
-        let mut beacons = Vec::with_capacity(beacon_len as usize + 1);
-        let mut offset = 0;
-        for _ in 0..beacon_len {
-            let beacon_flat = array_ref![data_flat, offset, SECP256K1_PUBLIC_KEY_LENGTH];
-            #[allow(clippy::ptr_offset_with_cast)]
-            let new_beacon = Secp256k1Pubkey::new(beacon_flat);
-            beacons.push(new_beacon);
-            offset += SECP256K1_PUBLIC_KEY_LENGTH;
+        let beacon_len = u8::from_le_bytes(*beacon_len) as usize;
+        if beacon_len > Self::capacity(src.len()) {
+            return Err(ProgramError::InvalidAccountData);
         }
+        let beacons = data_flat
+            .chunks_exact(SECP256K1_PUBLIC_KEY_LENGTH)
+            .take(beacon_len)
+            .map(Secp256k1Pubkey::new)
+            .collect();
 
         Ok(IncognitoProxy {
             is_initialized,
             bump_seed: u8::from_le_bytes(*bump_seed),
             vault: Pubkey::new_from_array(*vault_key),
+            start_height: u64::from_le_bytes(*start_height),
+            guardian: Pubkey::new_from_array(*guardian),
+            is_paused,
+            quorum_threshold: QuorumThreshold {
+                numerator: u8::from_le_bytes(*quorum_numerator),
+                denominator: u8::from_le_bytes(*quorum_denominator),
+            },
+            threshold_height: u64::from_le_bytes(*threshold_height),
             beacons
         })
     }
 
-    fn pack_into_slice(&self, dst: &mut [u8]) {
-        let dst = array_mut_ref![dst, 0, IncognitoProxy::LEN];
+    // [is_initialized, bump_seed, vault, beacon_len, beacons], without guardian the bridge cannot be paused
+    fn unpack_legacy(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, IncognitoProxy::LEGACY_LEN];
+        #[allow(clippy::ptr_offset_with_cast)]
+        let (is_initialized, bump_seed, vault_key, beacon_len, data_flat) = array_refs![
+            src,
+            1,
+            1,
+            PUBKEY_BYTES,
+            1,
+            SECP256K1_PUBLIC_KEY_LENGTH * IncognitoProxy::LEGACY_CAPACITY
+        ];
+        let beacon_len = u8::from_le_bytes(*beacon_len) as usize;
+        if beacon_len > Self::LEGACY_CAPACITY {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(IncognitoProxy {
+            is_initialized: unpack_bool(is_initialized)?,
+            bump_seed: u8::from_le_bytes(*bump_seed),
+            vault: Pubkey::new_from_array(*vault_key),
+            beacons: data_flat
+                .chunks_exact(SECP256K1_PUBLIC_KEY_LENGTH)
+                .take(beacon_len)
+                .map(Secp256k1Pubkey::new)
+                .collect(),
+            ..IncognitoProxy::default()
+        })
+    }
+
+    /// Packs an incognito proxy with the current layout version
+    pub fn pack(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        src.pack_into_slice(dst)
+    }
+
+    /// Fails when the beacons do not fit the account, or when the account is unversioned
+    /// and must be migrated first
+    pub fn pack_into_slice(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if dst.len() == Self::LEGACY_LEN {
+            msg!("Incognito proxy has the unversioned layout, migrate it first");
+            return Err(BridgeError::UnsupportedProxyVersion.into());
+        }
+        if self.beacons.len() > Self::capacity(dst.len()) || self.beacons.len() > MAX_BEACON_ADDRESSES {
+            msg!("{} beacons exceed capacity {}", self.beacons.len(), Self::capacity(dst.len()));
+            return Err(BridgeError::BeaconCapacityExceeded.into());
+        }
+        let (header, data_flat) = dst.split_at_mut(Self::HEADER_LEN);
+        let header = array_mut_ref![header, 0, IncognitoProxy::HEADER_LEN];
+        #[allow(clippy::ptr_offset_with_cast)]
         let (
             is_initialized,
+            version,
             bump_seed,
             vault,
+            start_height,
+            guardian,
+            is_paused,
+            quorum_numerator,
+            quorum_denominator,
+            threshold_height,
             beacon_len,
-            data_flat
         ) = mut_array_refs![
-            dst, 
-            1, 
+            header,
+            1,
+            1,
             1,
-            PUBKEY_BYTES, 
-            1, 
-            SECP256K1_PUBLIC_KEY_LENGTH * MAX_BEACON_ADDRESSES
+            PUBKEY_BYTES,
+            8,
+            PUBKEY_BYTES,
+            1,
+            1,
+            1,
+            8,
+            1
         ];
-        *beacon_len = u8::try_from(self.beacons.len()).unwrap().to_le_bytes();
+        *beacon_len = (self.beacons.len() as u8).to_le_bytes();
+        *version = PROXY_VERSION.to_le_bytes();
         *bump_seed = self.bump_seed.to_le_bytes();
         pack_bool(self.is_initialized, is_initialized);
         vault.copy_from_slice(self.vault.as_ref());
+        *start_height = self.start_height.to_le_bytes();
+        guardian.copy_from_slice(self.guardian.as_ref());
+        pack_bool(self.is_paused, is_paused);
+        *quorum_numerator = self.quorum_threshold.numerator.to_le_bytes();
+        *quorum_denominator = self.quorum_threshold.denominator.to_le_bytes();
+        *threshold_height = self.threshold_height.to_le_bytes();
 
-        let mut offset = 0;
-        // beacons
-        for beacon in &self.beacons {
-            let beacon_flat = array_mut_ref![data_flat, offset, SECP256K1_PUBLIC_KEY_LENGTH];
-            #[allow(clippy::ptr_offset_with_cast)]
+        // beacons, zero the slots of a previous larger committee
+        data_flat.fill(0);
+        for (beacon_flat, beacon) in data_flat.chunks_exact_mut(SECP256K1_PUBLIC_KEY_LENGTH).zip(&self.beacons) {
             beacon_flat.copy_from_slice(&beacon.to_bytes());
-            offset += SECP256K1_PUBLIC_KEY_LENGTH;
         }
+        Ok(())
+    }
+}
+
+/// ====== DAPP ALLOWLIST =======
+///
+/// Max number of programs reachable through dapp interaction
+pub const MAX_ALLOWED_PROGRAMS: usize = 32;
+/// Seed of the single allowlist of the program, derived from `find_program_address(&[ALLOWLIST_SEED])`.
+/// The upgrade authority binds it once to the incognito proxy holding user funds.
+pub const ALLOWLIST_SEED: &[u8] = b"allowlist";
 
+// Destination programs approved for dapp interaction
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DappAllowlist {
+    // init allowlist
+    pub is_initialized: bool,
+    // incognito proxy whose guardian manages the allowlist and whose pause stops dapp interaction
+    pub incognito_proxy: Pubkey,
+    /// allowed program ids
+    pub programs: Vec<Pubkey>,
+}
+
+impl IsInitialized for DappAllowlist {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
     }
+}
 
+impl Sealed for DappAllowlist {}
+
+impl Pack for DappAllowlist {
+    /// 1 + 32 + 1 + 32 * 32
+    const LEN: usize = 1058;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, DappAllowlist::LEN];
+        let (
+            is_initialized,
+            incognito_proxy,
+            programs_len,
+            data_flat
+        ) = array_refs![
+            src,
+            1,
+            PUBKEY_BYTES,
+            1,
+            PUBKEY_BYTES * MAX_ALLOWED_PROGRAMS
+        ];
+        let is_initialized = unpack_bool(is_initialized)?;
+        let programs_len = u8::from_le_bytes(*programs_len) as usize;
+        if programs_len > MAX_ALLOWED_PROGRAMS {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let programs = data_flat
+            .chunks(PUBKEY_BYTES)
+            .take(programs_len)
+            .map(Pubkey::new)
+            .collect();
+
+        Ok(DappAllowlist {
+            is_initialized,
+            incognito_proxy: Pubkey::new_from_array(*incognito_proxy),
+            programs,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, DappAllowlist::LEN];
+        let (
+            is_initialized,
+            incognito_proxy,
+            programs_len,
+            data_flat
+        ) = mut_array_refs![
+            dst,
+            1,
+            PUBKEY_BYTES,
+            1,
+            PUBKEY_BYTES * MAX_ALLOWED_PROGRAMS
+        ];
+        pack_bool(self.is_initialized, is_initialized);
+        incognito_proxy.copy_from_slice(self.incognito_proxy.as_ref());
+        *programs_len = u8::try_from(self.programs.len()).unwrap().to_le_bytes();
+        data_flat.fill(0);
+        for (program_flat, program) in data_flat.chunks_mut(PUBKEY_BYTES).zip(&self.programs) {
+            program_flat.copy_from_slice(program.as_ref());
+        }
+    }
+}
+
+/// ====== PROOF BUFFER =======
+///
+/// First byte of initialized proof buffers, no other account of the program starts with it
+pub const PROOF_BUFFER_ACCOUNT_TYPE: u8 = 0xb0;
+
+/// Header of an account holding an unshield proof uploaded in chunks,
+/// followed by the proof encoded as in `UnShield` instruction data.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProofBuffer {
+    // init proof buffer, stored as `PROOF_BUFFER_ACCOUNT_TYPE`
+    pub is_initialized: bool,
+    // only signer allowed to write and use the proof, receives the rent when closed
+    pub authority: Pubkey,
+}
+
+impl ProofBuffer {
+    /// Account size holding a proof of `proof_len` bytes
+    pub const fn space(proof_len: usize) -> usize {
+        Self::LEN + proof_len
+    }
+}
+
+impl IsInitialized for ProofBuffer {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Sealed for ProofBuffer {}
+
+impl Pack for ProofBuffer {
+    /// 1 + 32
+    const LEN: usize = 33;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, ProofBuffer::LEN];
+        let (account_type, authority) = array_refs![src, 1, PUBKEY_BYTES];
+        let is_initialized = match account_type[0] {
+            0 => false,
+            PROOF_BUFFER_ACCOUNT_TYPE => true,
+            account_type => {
+                msg!("Account type {} is not a proof buffer", account_type);
+                return Err(ProgramError::InvalidAccountData);
+            }
+        };
+        Ok(ProofBuffer {
+            is_initialized,
+            authority: Pubkey::new_from_array(*authority),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, ProofBuffer::LEN];
+        let (account_type, authority) = mut_array_refs![dst, 1, PUBKEY_BYTES];
+        account_type[0] = if self.is_initialized { PROOF_BUFFER_ACCOUNT_TYPE } else { 0 };
+        authority.copy_from_slice(self.authority.as_ref());
+    }
 }
 
 // Dapp interaction
-#[derive(Clone, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct DappRequest {
     // instruction
     pub inst: Vec<u8>,
     // number of accounts
-    pub num_acc: u8,
+    pub num_acc: u16,
     // sign acc index
-    pub sign_index: u8,
+    pub sign_index: u16,
+    // token balances required after the call
+    pub min_balances: Vec<BalanceAssertion>,
+    // token amounts the call may spend at most
+    pub max_spends: Vec<BalanceAssertion>,
+}
+
+// Token account balance checked after a dapp interaction
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BalanceAssertion {
+    // index of the token account in the forwarded accounts
+    pub account_index: u16,
+    // minimum balance after the call or maximum amount spent by the call
+    pub amount: u64,
+}
+
+// One call of a batched dapp interaction
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DappCall {
+    // index of the destination program in the forwarded accounts
+    pub program_index: u16,
+    // forwarded accounts passed to the program as (start, length) ranges
+    pub account_ranges: Vec<(u16, u16)>,
+    // instruction
+    pub inst: Vec<u8>,
+}
+
+// Dapp calls executed in order by one signer authority
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BatchDappRequest {
+    // calls in execution order
+    pub calls: Vec<DappCall>,
+    // number of accounts
+    pub num_acc: u16,
+    // sign acc index
+    pub sign_index: u16,
+}
+
+/// Beacon committee and vault of a new incognito proxy
+#[derive(Clone, Default, PartialEq)]
+pub struct InitBeaconRequest {
+    // vault key
+    pub vault: Pubkey,
+    // bump seed
+    pub bump_seed: u8,
+    // share of the beacon committee that must sign
+    pub quorum_threshold: QuorumThreshold,
+    // beacon list
+    pub beacons: Vec<Secp256k1Pubkey>,
+}
+
+// Secp256k1Pubkey does not implement Debug, print beacons as raw bytes
+impl fmt::Debug for InitBeaconRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InitBeaconRequest")
+            .field("vault", &self.vault)
+            .field("bump_seed", &self.bump_seed)
+            .field("quorum_threshold", &self.quorum_threshold)
+            .field("beacons", &self.beacons.iter().map(|beacon| beacon.0).collect::<Vec<_>>())
+            .finish()
+    }
 }
 
 /// Reserve liquidity
@@ -173,6 +580,82 @@ pub struct UnshieldRequest {
     pub signatures: Vec<[u8; 65]>
 }
 
+/// Beacon block signed by the committee
+#[derive(Clone, Debug, PartialEq)]
+pub struct BeaconBlockRequest {
+    // instruction root
+    pub inst_root: [u8; 32],
+    // blkData
+    pub blk_data: [u8; 32],
+    // signature index
+    pub indexes: Vec<u8>,
+    // signature
+    pub signatures: Vec<[u8; 65]>
+}
+
+/// Unshield instruction included in a verified beacon block
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifiedUnshieldRequest {
+    // instruction in bytes
+    pub inst: [u8; 162],
+    // beacon height
+    pub height: u64,
+    // inst paths to build merkle tree
+    pub inst_paths: Vec<[u8; 32]>,
+    // inst path indicator
+    pub inst_path_is_lefts: Vec<bool>,
+    // instruction root of the verified beacon block
+    pub inst_root: [u8; 32],
+}
+
+/// Unshield whose beacon signatures are checked by a preceding Secp256k1 program instruction
+#[derive(Clone, Debug, PartialEq)]
+pub struct Secp256k1UnshieldRequest {
+    // instruction in bytes
+    pub inst: [u8; 162],
+    // beacon height
+    pub height: u64,
+    // inst paths to build merkle tree
+    pub inst_paths: Vec<[u8; 32]>,
+    // inst path indicator
+    pub inst_path_is_lefts: Vec<bool>,
+    // instruction root
+    pub inst_root: [u8; 32],
+    // blkData
+    pub blk_data: [u8; 32],
+    // signature index, in the order of the Secp256k1 instruction signatures
+    pub indexes: Vec<u8>,
+}
+
+/// Beacon instruction signed by the beacon committee, such as a committee swap or a quorum threshold update
+#[derive(Clone, Debug, PartialEq)]
+pub struct BeaconInstructionRequest {
+    // beacon instruction in bytes
+    pub inst: Vec<u8>,
+    // beacon height
+    pub height: u64,
+    // inst paths to build merkle tree
+    pub inst_paths: Vec<[u8; 32]>,
+    // inst path indicator
+    pub inst_path_is_lefts: Vec<bool>,
+    // instruction root
+    pub inst_root: [u8; 32],
+    // blkData
+    pub blk_data: [u8; 32],
+    // signature index
+    pub indexes: Vec<u8>,
+    // signature 
+    pub signatures: Vec<[u8; 65]>
+}
+
 fn pack_bool(boolean: bool, dst: &mut [u8; 1]) {
     *dst = (boolean as u8).to_le_bytes()
+}
+
+fn unpack_bool(src: &[u8; 1]) -> Result<bool, ProgramError> {
+    match src {
+        [0] => Ok(false),
+        [1] => Ok(true),
+        _ => Err(BridgeError::InvalidBoolValue.into()),
+    }
 }
\ No newline at end of file
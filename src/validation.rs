@@ -0,0 +1,164 @@
+//! Account checks shared by the instruction processors
+
+use solana_program::{
+    account_info::AccountInfo,
+    bpf_loader_upgradeable,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    system_program,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::Account as TokenAccount;
+use crate::{error::BridgeError, state::IncognitoProxy};
+
+/// Account must sign the transaction
+pub fn assert_signer(account: &AccountInfo) -> ProgramResult {
+    if !account.is_signer {
+        msg!("Account {} must sign", account.key);
+        return Err(BridgeError::InvalidSigner.into());
+    }
+    Ok(())
+}
+
+/// Account must be writable
+pub fn assert_writable(account: &AccountInfo) -> ProgramResult {
+    if !account.is_writable {
+        msg!("Account {} must be writable", account.key);
+        return Err(BridgeError::AccountNotWritable.into());
+    }
+    Ok(())
+}
+
+/// Account must be owned by `owner`
+pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if account.owner != owner {
+        msg!("Account {} must be owned by {}", account.key, owner);
+        return Err(BridgeError::InvalidAccountOwner.into());
+    }
+    Ok(())
+}
+
+/// Account must be the spl token program
+pub fn assert_token_program(account: &AccountInfo) -> ProgramResult {
+    if *account.key != spl_token::id() {
+        msg!("Invalid token program {}", account.key);
+        return Err(BridgeError::InvalidTokenProgram.into());
+    }
+    Ok(())
+}
+
+/// Account must be the associated token account program
+pub fn assert_associated_token_program(account: &AccountInfo) -> ProgramResult {
+    if *account.key != spl_associated_token_account::id() {
+        msg!("Invalid associated token program {}", account.key);
+        return Err(BridgeError::InvalidAssociatedTokenProgram.into());
+    }
+    Ok(())
+}
+
+/// Account must be the system program
+pub fn assert_system_program(account: &AccountInfo) -> ProgramResult {
+    if *account.key != system_program::id() {
+        msg!("Invalid system program {}", account.key);
+        return Err(BridgeError::InvalidSystemProgram.into());
+    }
+    Ok(())
+}
+
+/// Account must be the pda of `seeds`, fails with `error` otherwise. Returns the bump seed
+pub fn assert_pda(
+    account: &AccountInfo,
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+    error: BridgeError,
+) -> Result<u8, ProgramError> {
+    let (pda, bump) = Pubkey::find_program_address(seeds, program_id);
+    if pda != *account.key {
+        msg!("Pda and key provided not match {}, {}", pda, account.key);
+        return Err(error.into());
+    }
+    Ok(bump)
+}
+
+/// Account must sign and be the upgrade authority stored in the program data account of the program
+pub fn assert_upgrade_authority(
+    program_data: &AccountInfo,
+    upgrade_authority: &AccountInfo,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let (program_data_address, _) = Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    if *program_data.key != program_data_address || *program_data.owner != bpf_loader_upgradeable::id() {
+        msg!("Invalid program data account {}", program_data.key);
+        return Err(BridgeError::InvalidUpgradeAuthority.into());
+    }
+    // UpgradeableLoaderState::ProgramData: u32 tag 3, u64 slot, then Option<Pubkey> upgrade authority
+    let program_data_data = program_data.try_borrow_data()?;
+    let is_upgrade_authority = program_data_data.len() >= 45
+        && program_data_data[..4] == 3u32.to_le_bytes()
+        && program_data_data[12] == 1
+        && program_data_data[13..45] == upgrade_authority.key.to_bytes();
+    if !upgrade_authority.is_signer || !is_upgrade_authority {
+        msg!("Only upgrade authority can perform this action");
+        return Err(BridgeError::InvalidUpgradeAuthority.into());
+    }
+    Ok(())
+}
+
+/// Account must be an initialized incognito proxy owned by the program
+pub fn assert_incognito_proxy(account: &AccountInfo, program_id: &Pubkey) -> Result<IncognitoProxy, ProgramError> {
+    assert_owned_by(account, program_id)?;
+    let incognito_proxy_info = IncognitoProxy::unpack_unchecked(&account.try_borrow_data()?)?;
+    if !incognito_proxy_info.is_initialized() {
+        return Err(BridgeError::BeaconsUnInitialized.into());
+    }
+    Ok(incognito_proxy_info)
+}
+
+/// Account must be the vault authority derived from `[incognito proxy, bump seed]`
+pub fn assert_vault_authority(
+    account: &AccountInfo,
+    incognito_proxy: &Pubkey,
+    bump_seed: u8,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    if vault_authority_address(incognito_proxy, bump_seed, program_id)? != *account.key {
+        msg!("Invalid vault authority {}", account.key);
+        return Err(BridgeError::InvalidVaultAuthority.into());
+    }
+    Ok(())
+}
+
+/// Account must be the signer authority derived from `[signer]`. Returns the bump seed
+pub fn assert_signer_authority(account: &AccountInfo, signer: &Pubkey, program_id: &Pubkey) -> Result<u8, ProgramError> {
+    assert_pda(account, &[signer.as_ref()], program_id, BridgeError::InvalidSignerTokenAuth)
+}
+
+/// Account must be a token account owned by the spl token program
+pub fn assert_token_account(account: &AccountInfo) -> Result<TokenAccount, ProgramError> {
+    assert_owned_by(account, &spl_token::id())?;
+    TokenAccount::unpack(&account.try_borrow_data()?)
+}
+
+/// Account must be the vault authority's associated token account. Returns its mint
+pub fn assert_vault_token_account(
+    account: &AccountInfo,
+    incognito_proxy: &Pubkey,
+    bump_seed: u8,
+    program_id: &Pubkey,
+) -> Result<Pubkey, ProgramError> {
+    let mint = assert_token_account(account)?.mint;
+    let vault_authority = vault_authority_address(incognito_proxy, bump_seed, program_id)?;
+    if get_associated_token_address(&vault_authority, &mint) != *account.key {
+        msg!("Only incognito proxy account will be accepted");
+        return Err(BridgeError::InvalidVaultTokenAccount.into());
+    }
+    Ok(mint)
+}
+
+fn vault_authority_address(incognito_proxy: &Pubkey, bump_seed: u8, program_id: &Pubkey) -> Result<Pubkey, ProgramError> {
+    Pubkey::create_program_address(&[incognito_proxy.as_ref(), &[bump_seed]], program_id)
+        .map_err(|_| BridgeError::InvalidVaultAuthority.into())
+}
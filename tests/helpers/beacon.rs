@@ -0,0 +1,56 @@
+use solana_program::{keccak::hash, secp256k1_recover::Secp256k1Pubkey};
+
+/// Beacon committee with deterministic secret keys to sign beacon blocks in tests.
+pub struct Committee {
+    keys: Vec<libsecp256k1::SecretKey>,
+}
+
+impl Committee {
+    pub fn new(size: usize, seed: u8) -> Self {
+        let keys = (0..size)
+            .map(|i| libsecp256k1::SecretKey::parse(&[seed, i as u8 + 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]).unwrap())
+            .collect();
+        Committee { keys }
+    }
+
+    pub fn beacons(&self) -> Vec<Secp256k1Pubkey> {
+        self.keys
+            .iter()
+            .map(|key| Secp256k1Pubkey::new(&libsecp256k1::PublicKey::from_secret_key(key).serialize()[1..]))
+            .collect()
+    }
+
+    /// Signs the double hash of blk_data and inst_root with the beacons at `indexes`.
+    pub fn sign(&self, blk_data: &[u8; 32], inst_root: &[u8; 32], indexes: &[u8]) -> Vec<[u8; 65]> {
+        let mut blk_data_bytes = blk_data.to_vec();
+        blk_data_bytes.extend_from_slice(inst_root);
+        let blk = hash(&hash(&blk_data_bytes).to_bytes()).to_bytes();
+        let message = libsecp256k1::Message::parse(&blk);
+        indexes
+            .iter()
+            .map(|index| {
+                let (signature, recovery_id) = libsecp256k1::sign(&message, &self.keys[*index as usize]);
+                let mut s_r_v = [0; 65];
+                s_r_v[..64].copy_from_slice(&signature.serialize());
+                s_r_v[64] = recovery_id.serialize();
+                s_r_v
+            })
+            .collect()
+    }
+}
+
+/// Leaf of the instruction merkle tree, a tree of a single instruction has it as root.
+pub fn inst_leaf(inst: &[u8], height: u64) -> [u8; 32] {
+    let mut inst_vec = inst.to_vec();
+    inst_vec.extend_from_slice(&[0; 24]);
+    inst_vec.extend_from_slice(&height.to_be_bytes());
+    hash(&inst_vec).to_bytes()
+}
+
+/// Big endian 256 bit encoding of a number as used in beacon instructions.
+pub fn u256(value: u64) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    bytes[24..].copy_from_slice(&value.to_be_bytes());
+    bytes
+}
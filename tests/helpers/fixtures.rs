@@ -0,0 +1,66 @@
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use solana_bridge::state::{DappAllowlist, IncognitoProxy, QuorumThreshold, DEFAULT_BEACON_CAPACITY};
+use spl_token::state::{Account as TokenAccount, AccountState};
+
+/// Initialized incognito proxy without beacons, fields are overridden with struct update syntax.
+pub fn proxy_info() -> IncognitoProxy {
+    IncognitoProxy {
+        is_initialized: true,
+        bump_seed: 255,
+        vault: Pubkey::new_unique(),
+        start_height: 0,
+        guardian: Pubkey::new_unique(),
+        is_paused: false,
+        quorum_threshold: QuorumThreshold::default(),
+        threshold_height: 0,
+        beacons: Vec::new(),
+    }
+}
+
+/// Incognito proxy account data with the default beacon capacity.
+pub fn proxy_data(incognito_proxy: IncognitoProxy) -> Vec<u8> {
+    let mut data = vec![0; IncognitoProxy::space(DEFAULT_BEACON_CAPACITY)];
+    IncognitoProxy::pack(incognito_proxy, &mut data).unwrap();
+    data
+}
+
+/// Initialized allowlist account data.
+pub fn allowlist_data(incognito_proxy: Pubkey, programs: Vec<Pubkey>) -> Vec<u8> {
+    let mut data = vec![0; DappAllowlist::LEN];
+    DappAllowlist::pack(
+        DappAllowlist {
+            is_initialized: true,
+            incognito_proxy,
+            programs,
+        },
+        &mut data,
+    )
+    .unwrap();
+    data
+}
+
+/// Initialized token account data.
+pub fn token_account_data(mint: Pubkey, owner: Pubkey, amount: u64) -> Vec<u8> {
+    let mut data = vec![0; TokenAccount::LEN];
+    TokenAccount::pack(
+        TokenAccount {
+            mint,
+            owner,
+            amount,
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+    data
+}
+
+/// Program data account of the upgradeable loader with `upgrade_authority`.
+pub fn program_data(upgrade_authority: &Pubkey) -> Vec<u8> {
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(upgrade_authority.as_ref());
+    data
+}
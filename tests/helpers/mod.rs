@@ -1,5 +1,8 @@
 #![allow(dead_code)]
 
+pub mod beacon;
+pub mod fixtures;
+
 use assert_matches::*;
 use solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey};
 use solana_program_test::*;
@@ -0,0 +1,284 @@
+mod helpers;
+
+use solana_program::{
+    account_info::AccountInfo,
+    bpf_loader_upgradeable,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+};
+use solana_bridge::{
+    error::BridgeError,
+    instruction::{find_allowlist_address, BridgeInstruction},
+    processor::process_instruction,
+    state::{DappAllowlist, DappRequest, IncognitoProxy, MAX_ALLOWED_PROGRAMS},
+};
+
+use crate::helpers::fixtures::{allowlist_data, program_data, proxy_data, proxy_info};
+
+// allowlist, incognito proxy and guardian accounts of an allowlist update
+fn process_allowlist_update(
+    program_id: &Pubkey,
+    proxy_key: &Pubkey,
+    allowlist_data: &mut [u8],
+    guardian: &Pubkey,
+    instruction: BridgeInstruction,
+) -> ProgramResult {
+    let (allowlist_key, _) = find_allowlist_address(program_id);
+    let mut proxy_data = proxy_data(IncognitoProxy { guardian: *guardian, ..proxy_info() });
+    let mut lamports = [0; 3];
+    let mut guardian_data = vec![];
+    let [allowlist_lamports, proxy_lamports, guardian_lamports] = &mut lamports;
+    let system_program = Pubkey::default();
+    let accounts = [
+        AccountInfo::new(&allowlist_key, false, true, allowlist_lamports, allowlist_data, program_id, false, 0),
+        AccountInfo::new(proxy_key, false, false, proxy_lamports, &mut proxy_data, program_id, false, 0),
+        AccountInfo::new(guardian, true, false, guardian_lamports, &mut guardian_data, &system_program, false, 0),
+    ];
+    process_instruction(program_id, &accounts, &instruction.pack())
+}
+
+// dapp interaction calling `program` without forwarded accounts
+fn process_dapp_interaction(
+    program_id: &Pubkey,
+    proxy_key: &Pubkey,
+    allowlist_key: &Pubkey,
+    allowlist_data: &mut [u8],
+    program: &Pubkey,
+) -> ProgramResult {
+    let signer = Pubkey::new_unique();
+    let mut proxy_data = proxy_data(proxy_info());
+    let mut lamports = [0; 4];
+    let mut empty_data = [vec![], vec![]];
+    let [signer_lamports, dest_lamports, proxy_lamports, allowlist_lamports] = &mut lamports;
+    let [signer_data, dest_data] = &mut empty_data;
+    let system_program = Pubkey::default();
+    let accounts = [
+        AccountInfo::new(&signer, true, false, signer_lamports, signer_data, &system_program, false, 0),
+        AccountInfo::new(program, false, false, dest_lamports, dest_data, &system_program, true, 0),
+        AccountInfo::new(proxy_key, false, false, proxy_lamports, &mut proxy_data, program_id, false, 0),
+        AccountInfo::new(allowlist_key, false, false, allowlist_lamports, allowlist_data, program_id, false, 0),
+    ];
+    process_instruction(
+        program_id,
+        &accounts,
+        &BridgeInstruction::DappInteraction {
+            dapp_request: DappRequest { inst: vec![1], num_acc: 0, sign_index: 0, ..DappRequest::default() },
+        }
+        .pack(),
+    )
+}
+
+// init allowlist signed by `signer`, the upgrade authority stored in the program data account is `upgrade_authority`
+fn process_init_allowlist(
+    program_id: &Pubkey,
+    program_data_key: &Pubkey,
+    upgrade_authority: &Pubkey,
+    signer: &Pubkey,
+    is_signer: bool,
+    allowlist_owner: &Pubkey,
+) -> ProgramResult {
+    let (allowlist_key, _) = find_allowlist_address(program_id);
+    let proxy_key = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let system_program = system_program::id();
+    let loader = bpf_loader_upgradeable::id();
+    let mut lamports = [0; 6];
+    let mut data = [vec![], proxy_data(proxy_info()), vec![], program_data(upgrade_authority), vec![], vec![]];
+    let [allowlist_lamports, proxy_lamports, signer_lamports, program_data_lamports, payer_lamports, system_lamports] =
+        &mut lamports;
+    let [allowlist_data, proxy_data, signer_data, program_data_data, payer_data, system_data] = &mut data;
+    let accounts = [
+        AccountInfo::new(&allowlist_key, false, true, allowlist_lamports, allowlist_data, allowlist_owner, false, 0),
+        AccountInfo::new(&proxy_key, false, false, proxy_lamports, proxy_data, program_id, false, 0),
+        AccountInfo::new(signer, is_signer, false, signer_lamports, signer_data, &system_program, false, 0),
+        AccountInfo::new(program_data_key, false, false, program_data_lamports, program_data_data, &loader, false, 0),
+        AccountInfo::new(&payer, true, true, payer_lamports, payer_data, &system_program, false, 0),
+        AccountInfo::new(&system_program, false, false, system_lamports, system_data, &system_program, true, 0),
+    ];
+    process_instruction(program_id, &accounts, &BridgeInstruction::InitAllowlist.pack())
+}
+
+#[test]
+fn test_add_and_remove_allowed_program() {
+    let program_id = Pubkey::new_unique();
+    let proxy_key = Pubkey::new_unique();
+    let guardian = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let mut data = allowlist_data(proxy_key, vec![]);
+
+    assert_eq!(
+        process_allowlist_update(&program_id, &proxy_key, &mut data, &guardian, BridgeInstruction::AddAllowedProgram { program }),
+        Ok(())
+    );
+    assert_eq!(DappAllowlist::unpack(&data).unwrap().programs, vec![program]);
+    assert_eq!(
+        process_allowlist_update(&program_id, &proxy_key, &mut data, &guardian, BridgeInstruction::AddAllowedProgram { program }),
+        Err(BridgeError::ProgramAlreadyAllowed.into())
+    );
+
+    assert_eq!(
+        process_allowlist_update(&program_id, &proxy_key, &mut data, &guardian, BridgeInstruction::RemoveAllowedProgram { program }),
+        Ok(())
+    );
+    assert!(DappAllowlist::unpack(&data).unwrap().programs.is_empty());
+    assert_eq!(
+        process_allowlist_update(&program_id, &proxy_key, &mut data, &guardian, BridgeInstruction::RemoveAllowedProgram { program }),
+        Err(BridgeError::ProgramNotAllowed.into())
+    );
+}
+
+#[test]
+fn test_add_allowed_program_full() {
+    let program_id = Pubkey::new_unique();
+    let proxy_key = Pubkey::new_unique();
+    let guardian = Pubkey::new_unique();
+    let programs = (0..MAX_ALLOWED_PROGRAMS).map(|_| Pubkey::new_unique()).collect();
+    let mut data = allowlist_data(proxy_key, programs);
+
+    assert_eq!(
+        process_allowlist_update(
+            &program_id,
+            &proxy_key,
+            &mut data,
+            &guardian,
+            BridgeInstruction::AddAllowedProgram { program: Pubkey::new_unique() },
+        ),
+        Err(BridgeError::AllowlistFull.into())
+    );
+}
+
+#[test]
+fn test_add_allowed_program_invalid_allowlist() {
+    let program_id = Pubkey::new_unique();
+    let proxy_key = Pubkey::new_unique();
+    let guardian = Pubkey::new_unique();
+    let mut data = allowlist_data(Pubkey::new_unique(), vec![]);
+
+    assert_eq!(
+        process_allowlist_update(
+            &program_id,
+            &proxy_key,
+            &mut data,
+            &guardian,
+            BridgeInstruction::AddAllowedProgram { program: Pubkey::new_unique() },
+        ),
+        Err(BridgeError::InvalidAllowlistAccount.into())
+    );
+}
+
+#[test]
+fn test_update_allowlist_invalid_guardian() {
+    let program_id = Pubkey::new_unique();
+    let proxy_key = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let mut data = allowlist_data(proxy_key, vec![program]);
+    let accounts_guardian = Pubkey::new_unique();
+    let mut proxy_data = proxy_data(proxy_info());
+    let (allowlist_key, _) = find_allowlist_address(&program_id);
+    let system_program = Pubkey::default();
+    let mut lamports = [0; 3];
+    let mut guardian_data = vec![];
+    let [allowlist_lamports, proxy_lamports, guardian_lamports] = &mut lamports;
+    let accounts = [
+        AccountInfo::new(&allowlist_key, false, true, allowlist_lamports, &mut data, &program_id, false, 0),
+        AccountInfo::new(&proxy_key, false, false, proxy_lamports, &mut proxy_data, &program_id, false, 0),
+        AccountInfo::new(&accounts_guardian, true, false, guardian_lamports, &mut guardian_data, &system_program, false, 0),
+    ];
+
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &BridgeInstruction::RemoveAllowedProgram { program }.pack()),
+        Err(BridgeError::InvalidGuardian.into())
+    );
+}
+
+#[test]
+fn test_dapp_interaction_program_not_allowed() {
+    let program_id = Pubkey::new_unique();
+    let proxy_key = Pubkey::new_unique();
+    let (allowlist_key, _) = find_allowlist_address(&program_id);
+    let mut data = allowlist_data(proxy_key, vec![Pubkey::new_unique()]);
+
+    assert_eq!(
+        process_dapp_interaction(&program_id, &proxy_key, &allowlist_key, &mut data, &Pubkey::new_unique()),
+        Err(BridgeError::ProgramNotAllowed.into())
+    );
+}
+
+#[test]
+fn test_init_allowlist() {
+    let program_id = Pubkey::new_unique();
+    let (program_data_key, _) = Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    let upgrade_authority = Pubkey::new_unique();
+    let system_program = system_program::id();
+
+    // checks pass up to the allowlist creation, which needs the rent sysvar
+    assert_eq!(
+        process_init_allowlist(&program_id, &program_data_key, &upgrade_authority, &upgrade_authority, true, &system_program),
+        Err(ProgramError::UnsupportedSysvar)
+    );
+    assert_eq!(
+        process_init_allowlist(&program_id, &program_data_key, &upgrade_authority, &upgrade_authority, true, &program_id),
+        Err(BridgeError::AccInitialized.into())
+    );
+}
+
+#[test]
+fn test_init_allowlist_invalid_upgrade_authority() {
+    let program_id = Pubkey::new_unique();
+    let (program_data_key, _) = Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    let upgrade_authority = Pubkey::new_unique();
+    let system_program = system_program::id();
+
+    for (program_data_key, signer, is_signer) in [
+        (program_data_key, Pubkey::new_unique(), true),
+        (program_data_key, upgrade_authority, false),
+        // program data of another program
+        (Pubkey::new_unique(), upgrade_authority, true),
+    ] {
+        assert_eq!(
+            process_init_allowlist(&program_id, &program_data_key, &upgrade_authority, &signer, is_signer, &system_program),
+            Err(BridgeError::InvalidUpgradeAuthority.into())
+        );
+    }
+}
+
+#[test]
+fn test_self_initialized_proxy_rejected() {
+    let program_id = Pubkey::new_unique();
+    let proxy_key = Pubkey::new_unique();
+    let (allowlist_key, _) = find_allowlist_address(&program_id);
+    let program = Pubkey::new_unique();
+    let mut data = allowlist_data(proxy_key, vec![program]);
+
+    // anyone can init a proxy with themselves as guardian, it does not manage the allowlist
+    let own_proxy = Pubkey::new_unique();
+    let own_guardian = Pubkey::new_unique();
+    assert_eq!(
+        process_allowlist_update(
+            &program_id,
+            &own_proxy,
+            &mut data,
+            &own_guardian,
+            BridgeInstruction::AddAllowedProgram { program: Pubkey::new_unique() },
+        ),
+        Err(BridgeError::InvalidAllowlistAccount.into())
+    );
+    assert_eq!(
+        process_dapp_interaction(&program_id, &own_proxy, &allowlist_key, &mut data, &program),
+        Err(BridgeError::InvalidAllowlistAccount.into())
+    );
+
+    // nor can it bring its own allowlist
+    let mut own_data = allowlist_data(own_proxy, vec![program]);
+    assert_eq!(
+        process_dapp_interaction(&program_id, &own_proxy, &Pubkey::new_unique(), &mut own_data, &program),
+        Err(BridgeError::InvalidAllowlistAccount.into())
+    );
+    assert_eq!(
+        process_dapp_interaction(&program_id, &proxy_key, &allowlist_key, &mut data, &program),
+        Ok(())
+    );
+}
@@ -0,0 +1,153 @@
+mod helpers;
+
+use proptest::{collection::vec, prelude::*};
+use solana_program::{pubkey::Pubkey, secp256k1_recover::Secp256k1Pubkey};
+use solana_bridge::{
+    beacon::{BeaconInstruction, UnshieldInstruction, U256, UNSHIELD_INST_LEN},
+    error::BridgeError,
+};
+
+use crate::helpers::beacon::{u256, Committee};
+
+fn unshield_inst(meta_type: u8, amount: [u8; 32]) -> Vec<u8> {
+    let mut inst = vec![meta_type, 1];
+    inst.extend_from_slice(&[2; 32]);
+    inst.extend_from_slice(&[3; 32]);
+    inst.extend_from_slice(&amount);
+    inst.extend_from_slice(&[4; 32]);
+    inst.extend_from_slice(&[5; 32]);
+    inst
+}
+
+#[test]
+fn test_decode_unshield() {
+    let mut amount = u256(1000);
+    amount[0] = 1;
+    let unshield = UnshieldInstruction {
+        token: Pubkey::new_from_array([2; 32]),
+        receiver: Pubkey::new_from_array([3; 32]),
+        amount: U256(amount),
+        tx_id: [4; 32],
+        reserved: [5; 32],
+    };
+    assert_eq!(
+        BeaconInstruction::decode(&unshield_inst(157, amount)).unwrap(),
+        BeaconInstruction::Unshield(unshield.clone())
+    );
+    assert_eq!(
+        BeaconInstruction::decode(&unshield_inst(158, amount)).unwrap(),
+        BeaconInstruction::UnshieldToPda(unshield.clone())
+    );
+
+    // the full amount is reported, converting it rejects instead of truncating
+    assert_eq!(unshield.amount.to_u64(), Err(BridgeError::InvalidBeaconInstruction.into()));
+    assert_eq!(U256(u256(1000)).to_u64(), Ok(1000));
+    assert_eq!(U256::from(u64::MAX).to_u64(), Ok(u64::MAX));
+}
+
+#[test]
+fn test_decode_unshield_invalid_length() {
+    let mut inst = unshield_inst(157, u256(1));
+    inst.push(0);
+    assert_eq!(
+        BeaconInstruction::decode(&inst).unwrap_err(),
+        BridgeError::InvalidBeaconInstruction.into()
+    );
+    assert_eq!(
+        BeaconInstruction::decode(&inst[..UNSHIELD_INST_LEN - 32]).unwrap_err(),
+        BridgeError::InvalidBeaconInstruction.into()
+    );
+}
+
+#[test]
+fn test_decode_swap_committee() {
+    let beacons = Committee::new(3, 1).beacons();
+    let inst = BeaconInstruction::SwapBeaconCommittee { start_height: U256::from(50), beacons: beacons.clone() }.encode();
+    assert_eq!(inst[..2], [70, 1]);
+    assert_eq!(inst.len(), 66 + 3 * 64);
+    match BeaconInstruction::decode(&inst).unwrap() {
+        BeaconInstruction::SwapBeaconCommittee { start_height, beacons: decoded } => {
+            assert_eq!(start_height.to_u64(), Ok(50));
+            assert!(decoded == beacons);
+        }
+        beacon_inst => panic!("unexpected {:?}", beacon_inst),
+    }
+
+    // the number of beacons must match the beacon list
+    let mut inst = inst;
+    inst.truncate(inst.len() - 1);
+    assert_eq!(
+        BeaconInstruction::decode(&inst).unwrap_err(),
+        BridgeError::InvalidBeaconInstruction.into()
+    );
+    let mut inst = vec![70, 1];
+    inst.extend_from_slice(&u256(50));
+    inst.extend_from_slice(&[0xff; 32]);
+    assert_eq!(
+        BeaconInstruction::decode(&inst).unwrap_err(),
+        BridgeError::InvalidBeaconInstruction.into()
+    );
+}
+
+#[test]
+fn test_decode_quorum_threshold() {
+    let mut inst = vec![159, 1];
+    inst.extend_from_slice(&u256(2));
+    inst.extend_from_slice(&u256(3));
+    assert_eq!(
+        BeaconInstruction::decode(&inst).unwrap(),
+        BeaconInstruction::SetQuorumThreshold { numerator: U256::from(2), denominator: U256::from(3) }
+    );
+    inst.push(0);
+    assert_eq!(
+        BeaconInstruction::decode(&inst).unwrap_err(),
+        BridgeError::InvalidBeaconInstruction.into()
+    );
+}
+
+#[test]
+fn test_decode_invalid_meta_type() {
+    for inst in [unshield_inst(156, u256(1)), unshield_inst(71, u256(1))] {
+        assert_eq!(BeaconInstruction::decode(&inst).unwrap_err(), BridgeError::InvalidMetaType.into());
+    }
+    let mut inst = unshield_inst(157, u256(1));
+    inst[1] = 0;
+    assert_eq!(BeaconInstruction::decode(&inst).unwrap_err(), BridgeError::InvalidMetaType.into());
+    assert_eq!(
+        BeaconInstruction::decode(&[157]).unwrap_err(),
+        BridgeError::InvalidBeaconInstruction.into()
+    );
+}
+
+fn beacon_instruction() -> impl Strategy<Value = BeaconInstruction> {
+    let unshield = (any::<[u8; 32]>(), any::<[u8; 32]>(), any::<[u8; 32]>(), any::<[u8; 32]>(), any::<[u8; 32]>())
+        .prop_map(|(token, receiver, amount, tx_id, reserved)| UnshieldInstruction {
+            token: Pubkey::new_from_array(token),
+            receiver: Pubkey::new_from_array(receiver),
+            amount: U256(amount),
+            tx_id,
+            reserved,
+        });
+    prop_oneof![
+        unshield.clone().prop_map(BeaconInstruction::Unshield),
+        unshield.prop_map(BeaconInstruction::UnshieldToPda),
+        (any::<[u8; 32]>(), vec(vec(any::<u8>(), 64), 0..8)).prop_map(|(start_height, beacons)| {
+            BeaconInstruction::SwapBeaconCommittee {
+                start_height: U256(start_height),
+                beacons: beacons.iter().map(|beacon| Secp256k1Pubkey::new(beacon)).collect(),
+            }
+        }),
+        (any::<[u8; 32]>(), any::<[u8; 32]>()).prop_map(|(numerator, denominator)| {
+            BeaconInstruction::SetQuorumThreshold { numerator: U256(numerator), denominator: U256(denominator) }
+        }),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn test_encode_decode(beacon_inst in beacon_instruction()) {
+        let inst = beacon_inst.encode();
+        prop_assert_eq!(inst[0], beacon_inst.meta_type());
+        prop_assert_eq!(BeaconInstruction::decode(&inst).unwrap(), beacon_inst);
+    }
+}
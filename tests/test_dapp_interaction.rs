@@ -0,0 +1,310 @@
+mod helpers;
+
+use std::sync::{Mutex, Once};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+};
+use solana_bridge::{
+    error::{dapp_error_code, BridgeError},
+    instruction::{find_allowlist_address, BridgeInstruction},
+    processor::process_instruction,
+    state::{BalanceAssertion, BatchDappRequest, DappCall, DappRequest},
+};
+use spl_token::state::Account as TokenAccount;
+
+use crate::helpers::fixtures::{allowlist_data, proxy_data, proxy_info, token_account_data};
+
+const CALLEE_BUILTIN_ERROR: u8 = 0;
+// return data set by a program the callee called in turn
+const NESTED_RETURN_DATA: u8 = 1;
+const CALLEE_RETURN_DATA: u8 = 2;
+// moves the amount that follows from the first account to the second one
+const CALLEE_TRANSFER: u8 = 3;
+const CALLEE_ERROR: u8 = 4;
+
+#[derive(Default)]
+struct Runtime {
+    // programs called with their account keys, in order
+    invoked: Vec<(Pubkey, Vec<Pubkey>)>,
+    // return data set by the called program
+    callee: Option<(Pubkey, Vec<u8>)>,
+    // return data set by the bridge
+    forwarded: Option<Vec<u8>>,
+}
+
+static RUNTIME: Mutex<Runtime> = Mutex::new(Runtime { invoked: Vec::new(), callee: None, forwarded: None });
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+static INIT_STUBS: Once = Once::new();
+
+// stands in for the runtime, the first byte of instruction data picks the callee behaviour
+struct DappStubs;
+
+impl SyscallStubs for DappStubs {
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let mut runtime = RUNTIME.lock().unwrap();
+        runtime.invoked.push((
+            instruction.program_id,
+            instruction.accounts.iter().map(|account| account.pubkey).collect(),
+        ));
+        match instruction.data[0] {
+            CALLEE_BUILTIN_ERROR => Err(ProgramError::InvalidArgument),
+            CALLEE_ERROR => Err(ProgramError::Custom(7)),
+            NESTED_RETURN_DATA => {
+                runtime.callee = Some((Pubkey::new_unique(), instruction.data[1..].to_vec()));
+                Ok(())
+            }
+            CALLEE_TRANSFER => {
+                let amount = u64::from_le_bytes(instruction.data[1..9].try_into().unwrap());
+                for (account_info, change) in account_infos.iter().zip([-1i128, 1]) {
+                    let mut token_account = TokenAccount::unpack(&account_info.data.borrow()).unwrap();
+                    token_account.amount = (token_account.amount as i128 + change * amount as i128) as u64;
+                    TokenAccount::pack(token_account, &mut account_info.data.borrow_mut()).unwrap();
+                }
+                Ok(())
+            }
+            _ => {
+                runtime.callee = Some((instruction.program_id, instruction.data[1..].to_vec()));
+                Ok(())
+            }
+        }
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RUNTIME.lock().unwrap().callee.clone()
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        RUNTIME.lock().unwrap().forwarded = Some(data.to_vec());
+    }
+}
+
+fn reset_runtime() {
+    INIT_STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(DappStubs));
+    });
+    *RUNTIME.lock().unwrap() = Runtime::default();
+}
+
+fn process_dapp_interaction(inst: Vec<u8>) -> ProgramResult {
+    reset_runtime();
+    let program_id = Pubkey::new_unique();
+    let mut keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    keys[3] = find_allowlist_address(&program_id).0;
+    let mut proxy_data = proxy_data(proxy_info());
+    let mut allowlist_data = allowlist_data(keys[2], vec![keys[1]]);
+    let mut lamports = [0; 4];
+    let mut empty_data = [vec![], vec![]];
+    let [signer_lamports, dest_lamports, proxy_lamports, allowlist_lamports] = &mut lamports;
+    let [signer_data, dest_data] = &mut empty_data;
+    let system_program = Pubkey::default();
+    let accounts = [
+        AccountInfo::new(&keys[0], true, false, signer_lamports, signer_data, &system_program, false, 0),
+        AccountInfo::new(&keys[1], false, false, dest_lamports, dest_data, &system_program, true, 0),
+        AccountInfo::new(&keys[2], false, false, proxy_lamports, &mut proxy_data, &program_id, false, 0),
+        AccountInfo::new(&keys[3], false, false, allowlist_lamports, &mut allowlist_data, &program_id, false, 0),
+    ];
+
+    process_instruction(
+        &program_id,
+        &accounts,
+        &BridgeInstruction::DappInteraction {
+            dapp_request: DappRequest { inst, num_acc: 0, sign_index: 0, ..DappRequest::default() },
+        }
+        .pack(),
+    )
+}
+
+#[test]
+fn test_dapp_interaction_builtin_error() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    assert_eq!(
+        process_dapp_interaction(vec![CALLEE_BUILTIN_ERROR]),
+        Err(BridgeError::DappInteractionFailed.into())
+    );
+    assert_eq!(RUNTIME.lock().unwrap().forwarded, None);
+}
+
+#[test]
+fn test_dapp_interaction_custom_error() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let err = process_dapp_interaction(vec![CALLEE_ERROR]).unwrap_err();
+    assert_eq!(dapp_error_code(&err), Some(7));
+    assert_eq!(dapp_error_code(&BridgeError::DappInteractionFailed.into()), None);
+}
+
+#[test]
+fn test_dapp_interaction_forwards_return_data() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    assert_eq!(process_dapp_interaction(vec![CALLEE_RETURN_DATA, 4, 2]), Ok(()));
+    assert_eq!(RUNTIME.lock().unwrap().forwarded, Some(vec![4, 2]));
+}
+
+#[test]
+fn test_dapp_interaction_ignores_nested_return_data() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    assert_eq!(process_dapp_interaction(vec![NESTED_RETURN_DATA, 4, 2]), Ok(()));
+    assert_eq!(RUNTIME.lock().unwrap().forwarded, None);
+}
+
+// swaps `amount_in` of a source token account holding 100 into a destination token account holding 0
+fn process_swap(
+    amount_in: u64,
+    min_balances: Vec<BalanceAssertion>,
+    max_spends: Vec<BalanceAssertion>,
+) -> ProgramResult {
+    reset_runtime();
+    let program_id = Pubkey::new_unique();
+    let mut keys: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_unique()).collect();
+    keys[5] = find_allowlist_address(&program_id).0;
+    let token_account = |amount| token_account_data(Pubkey::new_unique(), Pubkey::new_unique(), amount);
+    let mut data = vec![vec![], token_account(100), token_account(0), vec![], proxy_data(proxy_info())];
+    data.push(allowlist_data(keys[4], vec![keys[3]]));
+    let mut lamports = [0; 6];
+    let system_program = Pubkey::default();
+    let accounts: Vec<AccountInfo> = keys
+        .iter()
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .enumerate()
+        .map(|(i, ((key, lamports), data))| {
+            let owner = match i {
+                1 | 2 => &spl_token::ID,
+                4 | 5 => &program_id,
+                _ => &system_program,
+            };
+            AccountInfo::new(key, i == 0, i == 1 || i == 2, lamports, data, owner, i == 3, 0)
+        })
+        .collect();
+
+    let mut inst = vec![CALLEE_TRANSFER];
+    inst.extend_from_slice(&amount_in.to_le_bytes());
+    process_instruction(
+        &program_id,
+        &accounts,
+        &BridgeInstruction::DappInteraction {
+            dapp_request: DappRequest { inst, num_acc: 2, sign_index: 2, min_balances, max_spends },
+        }
+        .pack(),
+    )
+}
+
+#[test]
+fn test_dapp_interaction_balance_assertions() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let min_balance = vec![BalanceAssertion { account_index: 1, amount: 30 }];
+    let max_spend = vec![BalanceAssertion { account_index: 0, amount: 40 }];
+
+    assert_eq!(process_swap(30, min_balance.clone(), max_spend.clone()), Ok(()));
+    assert_eq!(
+        process_swap(29, min_balance.clone(), max_spend.clone()),
+        Err(BridgeError::BalanceBelowMinimum.into())
+    );
+    assert_eq!(process_swap(41, min_balance, max_spend), Err(BridgeError::SpendAboveMaximum.into()));
+}
+
+#[test]
+fn test_dapp_interaction_balance_assertion_invalid_account() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    assert_eq!(
+        process_swap(1, vec![BalanceAssertion { account_index: 2, amount: 0 }], vec![]),
+        Err(BridgeError::InvalidDappCall.into())
+    );
+    assert_eq!(
+        process_swap(1, vec![], vec![BalanceAssertion { account_index: 2, amount: 0 }]),
+        Err(BridgeError::InvalidDappCall.into())
+    );
+    // balances for max spends are read before the call
+    assert!(RUNTIME.lock().unwrap().invoked.is_empty());
+}
+
+// forwarded accounts are two programs followed by two plain accounts, only the programs are allowed
+fn process_batch_dapp_interaction(calls: Vec<DappCall>) -> (ProgramResult, Vec<Pubkey>) {
+    reset_runtime();
+    let program_id = Pubkey::new_unique();
+    let mut keys: Vec<Pubkey> = (0..7).map(|_| Pubkey::new_unique()).collect();
+    keys[6] = find_allowlist_address(&program_id).0;
+    let mut data = vec![vec![]; 5];
+    data.push(proxy_data(proxy_info()));
+    data.push(allowlist_data(keys[5], vec![keys[1], keys[2]]));
+    let mut lamports = [0; 7];
+    let system_program = Pubkey::default();
+    let accounts: Vec<AccountInfo> = keys
+        .iter()
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .enumerate()
+        .map(|(i, ((key, lamports), data))| {
+            let owner = if i >= 5 { &program_id } else { &system_program };
+            AccountInfo::new(key, i == 0, i == 3 || i == 4, lamports, data, owner, i == 1 || i == 2, 0)
+        })
+        .collect();
+
+    let result = process_instruction(
+        &program_id,
+        &accounts,
+        &BridgeInstruction::BatchDappInteraction {
+            batch_request: BatchDappRequest { calls, num_acc: 4, sign_index: 4 },
+        }
+        .pack(),
+    );
+    (result, keys)
+}
+
+#[test]
+fn test_batch_dapp_interaction_in_order() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let (result, keys) = process_batch_dapp_interaction(vec![
+        DappCall { program_index: 0, account_ranges: vec![(2, 2)], inst: vec![CALLEE_RETURN_DATA, 1] },
+        DappCall { program_index: 1, account_ranges: vec![(3, 1), (2, 1)], inst: vec![CALLEE_RETURN_DATA, 2] },
+    ]);
+    assert_eq!(result, Ok(()));
+
+    let runtime = RUNTIME.lock().unwrap();
+    assert_eq!(
+        runtime.invoked,
+        vec![(keys[1], vec![keys[3], keys[4]]), (keys[2], vec![keys[4], keys[3]])]
+    );
+    assert_eq!(runtime.forwarded, Some(vec![2]));
+}
+
+#[test]
+fn test_batch_dapp_interaction_stops_on_error() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let (result, keys) = process_batch_dapp_interaction(vec![
+        DappCall { program_index: 0, account_ranges: vec![], inst: vec![CALLEE_ERROR] },
+        DappCall { program_index: 1, account_ranges: vec![], inst: vec![CALLEE_RETURN_DATA] },
+    ]);
+    assert_eq!(dapp_error_code(&result.unwrap_err()), Some(7));
+    assert_eq!(RUNTIME.lock().unwrap().invoked, vec![(keys[1], vec![])]);
+}
+
+#[test]
+fn test_batch_dapp_interaction_invalid_call() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let valid_call = DappCall { program_index: 0, account_ranges: vec![(0, 4)], inst: vec![CALLEE_RETURN_DATA] };
+    let invalid_calls = vec![
+        (DappCall { program_index: 4, ..valid_call.clone() }, BridgeError::InvalidDappCall),
+        (DappCall { account_ranges: vec![(3, 2)], ..valid_call.clone() }, BridgeError::InvalidDappCall),
+        (DappCall { program_index: 2, ..valid_call.clone() }, BridgeError::ProgramNotAllowed),
+    ];
+
+    for (invalid_call, err) in invalid_calls {
+        let (result, _) = process_batch_dapp_interaction(vec![valid_call.clone(), invalid_call]);
+        assert_eq!(result, Err(err.into()));
+        // no call runs unless the whole batch is valid
+        assert!(RUNTIME.lock().unwrap().invoked.is_empty());
+    }
+    let (result, _) = process_batch_dapp_interaction(vec![]);
+    assert_eq!(result, Err(BridgeError::InvalidDappCall.into()));
+}
@@ -0,0 +1,45 @@
+use solana_program::pubkey::Pubkey;
+use solana_bridge::event::{decode_event, BridgeEvent};
+
+#[test]
+fn test_decode_event_round_trip() {
+    let events = vec![
+        BridgeEvent::Shield {
+            incognito_proxy: Pubkey::new_unique(),
+            inc_address: "12svfkP6w5UDJDSCwqH978PvqiqBxKmUnA9em9yAYWYJVRv7wuXY".to_string(),
+            token: Pubkey::new_unique(),
+            amount: 100,
+        },
+        BridgeEvent::Withdraw {
+            incognito_proxy: Pubkey::new_unique(),
+            signer: Pubkey::new_unique(),
+            inc_address: "12svfkP6w5UDJDSCwqH978PvqiqBxKmUnA9em9yAYWYJVRv7wuXY".to_string(),
+            token: Pubkey::new_unique(),
+            amount: 200,
+        },
+        BridgeEvent::Unshield {
+            incognito_proxy: Pubkey::new_unique(),
+            tx_id: [3; 32],
+            meta_type: 157,
+            receiver: Pubkey::new_unique(),
+            token: Pubkey::new_unique(),
+            amount: 300,
+        },
+        BridgeEvent::CommitteeUpdated {
+            incognito_proxy: Pubkey::new_unique(),
+            start_height: 10,
+            beacons: vec![[1; 64], [2; 64]],
+        },
+    ];
+    for event in events {
+        let data = borsh::BorshSerialize::try_to_vec(&event).unwrap();
+        assert_eq!(decode_event(&data).unwrap(), event);
+    }
+}
+
+#[test]
+fn test_decode_event_invalid_data() {
+    assert!(decode_event(&[]).is_err());
+    assert!(decode_event(&[0, 1, 2]).is_err());
+    assert!(decode_event(&[200]).is_err());
+}
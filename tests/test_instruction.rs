@@ -0,0 +1,672 @@
+use proptest::{collection::vec, prelude::*};
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey, secp256k1_recover::Secp256k1Pubkey};
+use solana_bridge::{
+    error::BridgeError,
+    instruction::{
+        batch_dapp_interaction, dapp_interaction, dapp_interaction_with_assertions, find_allowlist_address,
+        find_signer_authority_address, unshield, unshield_and_call, unshield_from_buffer, unshield_verified,
+        withdraw_request, BridgeInstruction,
+    },
+    state::{
+        BalanceAssertion, BatchDappRequest, BeaconBlockRequest, DappCall, DappRequest, InitBeaconRequest, QuorumThreshold,
+        Secp256k1UnshieldRequest, BeaconInstructionRequest, UnshieldRequest, VerifiedUnshieldRequest, BURN_TX_SEED,
+        VERIFIED_ROOT_SEED,
+    },
+};
+use spl_associated_token_account::get_associated_token_address;
+
+fn bytes148() -> impl Strategy<Value = [u8; 148]> {
+    vec(any::<u8>(), 148).prop_map(|v| v.try_into().unwrap())
+}
+
+fn bytes162() -> impl Strategy<Value = [u8; 162]> {
+    vec(any::<u8>(), 162).prop_map(|v| v.try_into().unwrap())
+}
+
+fn bytes65() -> impl Strategy<Value = [u8; 65]> {
+    vec(any::<u8>(), 65).prop_map(|v| v.try_into().unwrap())
+}
+
+fn beacon() -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), 64)
+}
+
+fn unshield_request() -> impl Strategy<Value = UnshieldRequest> {
+    (
+        bytes162(),
+        any::<u64>(),
+        (0..16usize).prop_flat_map(|depth| (vec(any::<[u8; 32]>(), depth), vec(any::<bool>(), depth))),
+        any::<[u8; 32]>(),
+        any::<[u8; 32]>(),
+        (0..20usize).prop_flat_map(|num_sigs| (vec(any::<u8>(), num_sigs), vec(bytes65(), num_sigs))),
+    )
+        .prop_map(
+            |(inst, height, (inst_paths, inst_path_is_lefts), inst_root, blk_data, (indexes, signatures))| {
+                UnshieldRequest {
+                    inst,
+                    height,
+                    inst_paths,
+                    inst_path_is_lefts,
+                    inst_root,
+                    blk_data,
+                    indexes,
+                    signatures,
+                }
+            },
+        )
+}
+
+fn dapp_request() -> impl Strategy<Value = DappRequest> {
+    (
+        vec(any::<u8>(), 0..1024),
+        any::<u16>(),
+        any::<u16>(),
+        vec(balance_assertion(), 0..4),
+        vec(balance_assertion(), 0..4),
+    )
+        .prop_map(|(inst, num_acc, sign_index, min_balances, max_spends)| DappRequest {
+            inst,
+            num_acc,
+            sign_index,
+            min_balances,
+            max_spends,
+        })
+}
+
+fn balance_assertion() -> impl Strategy<Value = BalanceAssertion> {
+    (any::<u16>(), any::<u64>()).prop_map(|(account_index, amount)| BalanceAssertion { account_index, amount })
+}
+
+fn dapp_call() -> impl Strategy<Value = DappCall> {
+    (any::<u16>(), vec(any::<(u16, u16)>(), 0..8), vec(any::<u8>(), 0..300)).prop_map(
+        |(program_index, account_ranges, inst)| DappCall { program_index, account_ranges, inst },
+    )
+}
+
+fn bridge_instruction() -> impl Strategy<Value = BridgeInstruction> {
+    prop_oneof![
+        (any::<u64>(), bytes148())
+            .prop_map(|(amount, inc_address)| BridgeInstruction::Shield { amount, inc_address }),
+        unshield_request().prop_map(|unshield_info| BridgeInstruction::UnShield { unshield_info }),
+        (any::<[u8; 32]>(), any::<u8>(), vec(beacon(), 0..20), any::<(u8, u8)>()).prop_map(
+            |(vault, bump_seed, beacons, (numerator, denominator))| BridgeInstruction::InitBeacon {
+                init_beacon_info: InitBeaconRequest {
+                    vault: Pubkey::new_from_array(vault),
+                    bump_seed,
+                    quorum_threshold: QuorumThreshold { numerator, denominator },
+                    beacons: beacons.iter().map(|beacon| Secp256k1Pubkey::new(beacon)).collect(),
+                },
+            },
+        ),
+        dapp_request().prop_map(|dapp_request| BridgeInstruction::DappInteraction { dapp_request }),
+        (any::<u64>(), bytes148())
+            .prop_map(|(amount, inc_address)| BridgeInstruction::WithdrawRequest { amount, inc_address }),
+        (any::<u64>(), bytes148())
+            .prop_map(|(amount, inc_address)| BridgeInstruction::ShieldNative { amount, inc_address }),
+        Just(BridgeInstruction::Pause),
+        Just(BridgeInstruction::Unpause),
+        any::<[u8; 32]>().prop_map(|program| BridgeInstruction::AddAllowedProgram {
+            program: Pubkey::new_from_array(program),
+        }),
+        any::<[u8; 32]>().prop_map(|program| BridgeInstruction::RemoveAllowedProgram {
+            program: Pubkey::new_from_array(program),
+        }),
+        (vec(dapp_call(), 0..8), any::<u16>(), any::<u16>()).prop_map(|(calls, num_acc, sign_index)| {
+            BridgeInstruction::BatchDappInteraction {
+                batch_request: BatchDappRequest { calls, num_acc, sign_index },
+            }
+        }),
+        (unshield_request(), dapp_request()).prop_map(|(unshield_info, dapp_request)| {
+            BridgeInstruction::UnshieldAndCall { unshield_info, dapp_request }
+        }),
+        (bytes148(), any::<bool>())
+            .prop_map(|(inc_address, close_accounts)| BridgeInstruction::WithdrawAll { inc_address, close_accounts }),
+        beacon_instruction_request().prop_map(|swap_info| BridgeInstruction::SwapBeaconCommittee { swap_info }),
+        beacon_instruction_request()
+            .prop_map(|threshold_info| BridgeInstruction::SetQuorumThreshold { threshold_info }),
+        any::<u8>().prop_map(|capacity| BridgeInstruction::ResizeProxy { capacity }),
+        Just(BridgeInstruction::InitProofBuffer),
+        (any::<u32>(), vec(any::<u8>(), 0..1024))
+            .prop_map(|(offset, data)| BridgeInstruction::WriteProofChunk { offset, data }),
+        Just(BridgeInstruction::UnshieldFromBuffer),
+        unshield_request().prop_map(|proof| BridgeInstruction::VerifyBeaconBlock {
+            block_info: BeaconBlockRequest {
+                inst_root: proof.inst_root,
+                blk_data: proof.blk_data,
+                indexes: proof.indexes,
+                signatures: proof.signatures,
+            },
+        }),
+        unshield_request().prop_map(|proof| BridgeInstruction::UnshieldVerified {
+            unshield_info: VerifiedUnshieldRequest {
+                inst: proof.inst,
+                height: proof.height,
+                inst_paths: proof.inst_paths,
+                inst_path_is_lefts: proof.inst_path_is_lefts,
+                inst_root: proof.inst_root,
+            },
+        }),
+        unshield_request().prop_map(|proof| BridgeInstruction::UnshieldSecp256k1 {
+            unshield_info: Secp256k1UnshieldRequest {
+                inst: proof.inst,
+                height: proof.height,
+                inst_paths: proof.inst_paths,
+                inst_path_is_lefts: proof.inst_path_is_lefts,
+                inst_root: proof.inst_root,
+                blk_data: proof.blk_data,
+                indexes: proof.indexes,
+            },
+        }),
+        Just(BridgeInstruction::InitAllowlist),
+        Just(BridgeInstruction::MigrateProxy),
+        Just(BridgeInstruction::CloseProofBuffer),
+    ]
+}
+
+fn beacon_instruction_request() -> impl Strategy<Value = BeaconInstructionRequest> {
+    (vec(any::<u8>(), 0..1400), unshield_request()).prop_map(|(inst, proof)| BeaconInstructionRequest {
+        inst,
+        height: proof.height,
+        inst_paths: proof.inst_paths,
+        inst_path_is_lefts: proof.inst_path_is_lefts,
+        inst_root: proof.inst_root,
+        blk_data: proof.blk_data,
+        indexes: proof.indexes,
+        signatures: proof.signatures,
+    })
+}
+
+proptest! {
+    #[test]
+    fn test_pack_unpack_round_trip(instruction in bridge_instruction()) {
+        let packed = instruction.pack();
+        prop_assert_eq!(BridgeInstruction::unpack(&packed).unwrap(), instruction);
+    }
+
+    #[test]
+    fn test_pack_is_canonical(instruction in bridge_instruction()) {
+        let packed = instruction.pack();
+        prop_assert_eq!(BridgeInstruction::unpack(&packed).unwrap().pack(), packed);
+    }
+
+    #[test]
+    fn test_unpack_arbitrary_input_does_not_panic(input in vec(any::<u8>(), 0..2048)) {
+        let _ = BridgeInstruction::unpack(&input);
+    }
+
+    #[test]
+    fn test_unpack_unshield_trailing_bytes(unshield_info in unshield_request(), extra in vec(any::<u8>(), 1..8)) {
+        let mut packed = BridgeInstruction::UnShield { unshield_info }.pack();
+        packed.extend_from_slice(&extra);
+        prop_assert_eq!(
+            BridgeInstruction::unpack(&packed).unwrap_err(),
+            BridgeError::TrailingInstructionData.into()
+        );
+    }
+
+    #[test]
+    fn test_unpack_trailing_bytes(instruction in bridge_instruction(), extra in vec(any::<u8>(), 1..8)) {
+        let mut packed = instruction.pack();
+        packed.extend_from_slice(&extra);
+        prop_assert_eq!(
+            BridgeInstruction::unpack(&packed).unwrap_err(),
+            BridgeError::TrailingInstructionData.into()
+        );
+    }
+
+    #[test]
+    fn test_unpack_unshield_is_lefts_length_mismatch(mut unshield_info in unshield_request(), is_left in any::<bool>()) {
+        unshield_info.inst_path_is_lefts.push(is_left);
+        let packed = BridgeInstruction::UnShield { unshield_info }.pack();
+        prop_assert_eq!(
+            BridgeInstruction::unpack(&packed).unwrap_err(),
+            BridgeError::InstPathLengthMismatch.into()
+        );
+    }
+
+    #[test]
+    fn test_unpack_unshield_signatures_length_mismatch(mut unshield_info in unshield_request(), index in any::<u8>()) {
+        unshield_info.indexes.push(index);
+        let packed = BridgeInstruction::UnShield { unshield_info }.pack();
+        prop_assert_eq!(
+            BridgeInstruction::unpack(&packed).unwrap_err(),
+            BridgeError::SignatureLengthMismatch.into()
+        );
+    }
+
+    #[test]
+    fn test_unpack_unshield_invalid_bool(mut unshield_info in unshield_request(), value in 2u8..) {
+        unshield_info.inst_paths = vec![[0; 32]];
+        unshield_info.inst_path_is_lefts = vec![true];
+        let mut packed = BridgeInstruction::UnShield { unshield_info }.pack();
+        // tag, inst, height, paths length, path, is lefts length
+        packed[1 + 162 + 8 + 1 + 32 + 1] = value;
+        prop_assert_eq!(
+            BridgeInstruction::unpack(&packed).unwrap_err(),
+            BridgeError::InvalidBoolValue.into()
+        );
+    }
+
+    #[test]
+    fn test_unpack_truncated_input_fails(instruction in bridge_instruction(), cut in any::<prop::sample::Index>()) {
+        let packed = instruction.pack();
+        let truncated = &packed[..cut.index(packed.len())];
+        prop_assert!(BridgeInstruction::unpack(truncated).is_err());
+    }
+}
+
+#[test]
+fn test_unpack_unshield_left_path() {
+    let unshield_info = UnshieldRequest {
+        inst: [0; 162],
+        height: 1,
+        inst_paths: vec![[1; 32], [2; 32]],
+        inst_path_is_lefts: vec![true, false],
+        inst_root: [0; 32],
+        blk_data: [0; 32],
+        indexes: Vec::new(),
+        signatures: Vec::new(),
+    };
+    let packed = BridgeInstruction::UnShield { unshield_info }.pack();
+    match BridgeInstruction::unpack(&packed).unwrap() {
+        BridgeInstruction::UnShield { unshield_info } => {
+            assert_eq!(unshield_info.inst_path_is_lefts, vec![true, false]);
+        }
+        _ => panic!("unexpected instruction"),
+    }
+}
+
+#[test]
+fn test_withdraw_request_accounts() {
+    let program_id = Pubkey::new_unique();
+    let signer = Pubkey::new_unique();
+    let incognito_proxy = Pubkey::new_unique();
+    let token_mint = Pubkey::new_unique();
+    let (vault_authority, bump_seed) = Pubkey::find_program_address(&[incognito_proxy.as_ref()], &program_id);
+    let (signer_authority, _) = find_signer_authority_address(&program_id, &signer);
+
+    let instruction = withdraw_request(program_id, 10, signer, incognito_proxy, bump_seed, token_mint, &[1; 148]);
+    let keys: Vec<Pubkey> = instruction.accounts.iter().map(|account| account.pubkey).collect();
+    assert_eq!(
+        keys,
+        vec![
+            get_associated_token_address(&signer_authority, &token_mint),
+            get_associated_token_address(&vault_authority, &token_mint),
+            incognito_proxy,
+            signer,
+            signer_authority,
+            spl_token::id(),
+        ]
+    );
+    assert!(instruction.accounts[3].is_signer);
+    assert_eq!(
+        BridgeInstruction::unpack(&instruction.data).unwrap(),
+        BridgeInstruction::WithdrawRequest { amount: 10, inc_address: [1; 148] }
+    );
+}
+
+#[test]
+fn test_dapp_interaction_signer_authority() {
+    let program_id = Pubkey::new_unique();
+    let signer = Pubkey::new_unique();
+    let program_dest = Pubkey::new_unique();
+    let (signer_authority, _) = find_signer_authority_address(&program_id, &signer);
+    let accounts = vec![
+        AccountMeta::new(Pubkey::new_unique(), false),
+        AccountMeta::new_readonly(signer_authority, true),
+    ];
+
+    let incognito_proxy = Pubkey::new_unique();
+
+    let instruction = dapp_interaction(program_id, signer, incognito_proxy, program_dest, accounts, vec![7, 7]);
+    assert_eq!(instruction.accounts.len(), 6);
+    assert!(instruction.accounts[0].is_signer);
+    assert!(!instruction.accounts[2].is_signer);
+    assert_eq!(instruction.accounts[3].pubkey, program_dest);
+    assert_eq!(instruction.accounts[4].pubkey, incognito_proxy);
+    assert_eq!(instruction.accounts[5].pubkey, find_allowlist_address(&program_id).0);
+    assert_eq!(
+        BridgeInstruction::unpack(&instruction.data).unwrap(),
+        BridgeInstruction::DappInteraction {
+            dapp_request: DappRequest { inst: vec![7, 7], num_acc: 2, sign_index: 1, ..DappRequest::default() },
+        }
+    );
+}
+
+#[test]
+fn test_pack_dapp_interaction_wide_lengths() {
+    let narrow = BridgeInstruction::DappInteraction {
+        dapp_request: DappRequest { inst: vec![1; 255], num_acc: 255, sign_index: 0, ..DappRequest::default() },
+    };
+    let packed = narrow.pack();
+    assert_eq!(packed[..2], [3, 255]);
+    assert_eq!(BridgeInstruction::unpack(&packed).unwrap(), narrow);
+
+    let long_inst = BridgeInstruction::DappInteraction {
+        dapp_request: DappRequest { inst: vec![1; 256], num_acc: 2, sign_index: 1, ..DappRequest::default() },
+    };
+    let packed = long_inst.pack();
+    assert_eq!(packed[..5], [12, 0, 1, 0, 0]);
+    // num_acc, sign_index and empty balance assertions
+    assert_eq!(packed[261..], [2, 0, 1, 0, 0, 0]);
+    assert_eq!(BridgeInstruction::unpack(&packed).unwrap(), long_inst);
+
+    let many_accounts = BridgeInstruction::DappInteraction {
+        dapp_request: DappRequest { inst: vec![1], num_acc: 300, sign_index: 300, ..DappRequest::default() },
+    };
+    let packed = many_accounts.pack();
+    assert_eq!(packed[0], 12);
+    assert_eq!(BridgeInstruction::unpack(&packed).unwrap(), many_accounts);
+}
+
+#[test]
+fn test_dapp_interaction_with_assertions() {
+    let program_id = Pubkey::new_unique();
+    let source = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+    let accounts = vec![AccountMeta::new(source, false), AccountMeta::new(destination, false)];
+
+    let instruction = dapp_interaction_with_assertions(
+        program_id,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        accounts,
+        vec![9],
+        vec![BalanceAssertion { account_index: 1, amount: 100 }],
+        vec![BalanceAssertion { account_index: 0, amount: 50 }],
+    );
+    assert_eq!(instruction.data[0], 12);
+    assert_eq!(
+        BridgeInstruction::unpack(&instruction.data).unwrap(),
+        BridgeInstruction::DappInteraction {
+            dapp_request: DappRequest {
+                inst: vec![9],
+                num_acc: 2,
+                sign_index: 2,
+                min_balances: vec![BalanceAssertion { account_index: 1, amount: 100 }],
+                max_spends: vec![BalanceAssertion { account_index: 0, amount: 50 }],
+            },
+        }
+    );
+}
+
+#[test]
+fn test_batch_dapp_interaction_accounts() {
+    let program_id = Pubkey::new_unique();
+    let signer = Pubkey::new_unique();
+    let incognito_proxy = Pubkey::new_unique();
+    let program_dest = Pubkey::new_unique();
+    let (signer_authority, _) = find_signer_authority_address(&program_id, &signer);
+    let accounts = vec![
+        AccountMeta::new_readonly(program_dest, false),
+        AccountMeta::new(signer_authority, true),
+        AccountMeta::new(Pubkey::new_unique(), false),
+    ];
+    let calls = vec![
+        DappCall { program_index: 0, account_ranges: vec![(1, 2)], inst: vec![1] },
+        DappCall { program_index: 0, account_ranges: vec![(2, 1), (1, 1)], inst: vec![2] },
+    ];
+
+    let instruction = batch_dapp_interaction(program_id, signer, incognito_proxy, accounts, calls.clone());
+    assert_eq!(instruction.accounts.len(), 6);
+    assert!(instruction.accounts[0].is_signer);
+    assert!(!instruction.accounts[2].is_signer);
+    assert_eq!(instruction.accounts[4].pubkey, incognito_proxy);
+    assert_eq!(instruction.accounts[5].pubkey, find_allowlist_address(&program_id).0);
+    assert_eq!(
+        BridgeInstruction::unpack(&instruction.data).unwrap(),
+        BridgeInstruction::BatchDappInteraction {
+            batch_request: BatchDappRequest { calls, num_acc: 3, sign_index: 1 },
+        }
+    );
+}
+
+#[test]
+fn test_pack_batch_dapp_interaction_wide_lengths() {
+    let call = DappCall { program_index: 255, account_ranges: vec![(0, 255)], inst: vec![1; 255] };
+    let narrow = BridgeInstruction::BatchDappInteraction {
+        batch_request: BatchDappRequest { calls: vec![call.clone()], num_acc: 255, sign_index: 255 },
+    };
+    let packed = narrow.pack();
+    assert_eq!(packed[..4], [11, 255, 255, 1]);
+    assert_eq!(BridgeInstruction::unpack(&packed).unwrap(), narrow);
+
+    let long_inst = BridgeInstruction::BatchDappInteraction {
+        batch_request: BatchDappRequest {
+            calls: vec![DappCall { inst: vec![1; 256], ..call.clone() }],
+            num_acc: 2,
+            sign_index: 1,
+        },
+    };
+    let packed = long_inst.pack();
+    // num_acc, sign_index, one call with its program index, one range and u32 instruction length
+    assert_eq!(packed[..20], [27, 2, 0, 1, 0, 1, 255, 0, 1, 0, 0, 255, 0, 0, 1, 0, 0, 1, 1, 1]);
+    assert_eq!(BridgeInstruction::unpack(&packed).unwrap(), long_inst);
+
+    // more than 255 forwarded accounts, previously rejected by the builder
+    let accounts = (0..300).map(|_| AccountMeta::new(Pubkey::new_unique(), false)).collect();
+    let calls = vec![DappCall { program_index: 299, account_ranges: vec![(256, 44)], inst: vec![1] }];
+    let (program_id, signer, incognito_proxy) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let instruction = batch_dapp_interaction(program_id, signer, incognito_proxy, accounts, calls.clone());
+    assert_eq!(instruction.data[0], 27);
+    assert_eq!(
+        BridgeInstruction::unpack(&instruction.data).unwrap(),
+        BridgeInstruction::BatchDappInteraction {
+            batch_request: BatchDappRequest { calls, num_acc: 300, sign_index: 300 },
+        }
+    );
+    let mut trailing = instruction.data;
+    trailing.push(0);
+    assert_eq!(BridgeInstruction::unpack(&trailing).unwrap_err(), BridgeError::TrailingInstructionData.into());
+}
+
+#[test]
+fn test_unshield_burn_tx_account() {
+    let program_id = Pubkey::new_unique();
+    let incognito_proxy = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let (_, bump_seed) = Pubkey::find_program_address(&[incognito_proxy.as_ref()], &program_id);
+    let mut inst = [0; 162];
+    inst[98..130].copy_from_slice(&[5; 32]);
+    let unshield_info = UnshieldRequest {
+        inst,
+        height: 1,
+        inst_paths: Vec::new(),
+        inst_path_is_lefts: Vec::new(),
+        inst_root: [0; 32],
+        blk_data: [0; 32],
+        indexes: Vec::new(),
+        signatures: Vec::new(),
+    };
+
+    let unshield_maker = Pubkey::new_unique();
+    let instruction = unshield(
+        program_id,
+        unshield_info,
+        unshield_maker,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        incognito_proxy,
+        bump_seed,
+        Pubkey::new_unique(),
+        payer,
+    );
+    let (burn_tx, _) = Pubkey::find_program_address(&[BURN_TX_SEED, incognito_proxy.as_ref(), &[5; 32]], &program_id);
+    assert_eq!(instruction.accounts.len(), 14);
+    assert_eq!(instruction.accounts[7].pubkey, burn_tx);
+    assert!(instruction.accounts[7].is_writable);
+    assert_eq!(instruction.accounts[8].pubkey, payer);
+    assert!(instruction.accounts[8].is_signer);
+    assert_eq!(instruction.accounts[10].pubkey, unshield_maker);
+    assert_eq!(instruction.accounts[12].pubkey, spl_associated_token_account::id());
+}
+
+#[test]
+fn test_unshield_from_buffer_accounts() {
+    let program_id = Pubkey::new_unique();
+    let incognito_proxy = Pubkey::new_unique();
+    let proof_buffer = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let (_, bump_seed) = Pubkey::find_program_address(&[incognito_proxy.as_ref()], &program_id);
+    let unshield_info = UnshieldRequest {
+        inst: [0; 162],
+        height: 1,
+        inst_paths: Vec::new(),
+        inst_path_is_lefts: Vec::new(),
+        inst_root: [0; 32],
+        blk_data: [0; 32],
+        indexes: Vec::new(),
+        signatures: Vec::new(),
+    };
+    let [unshield_maker, unshield_token_account, vault_account, token_mint, payer] = [(); 5].map(|_| Pubkey::new_unique());
+
+    let instruction = unshield_from_buffer(
+        program_id,
+        proof_buffer,
+        authority,
+        unshield_info.clone(),
+        unshield_maker,
+        unshield_token_account,
+        vault_account,
+        incognito_proxy,
+        bump_seed,
+        token_mint,
+        payer,
+    );
+    let unshield_accounts = unshield(
+        program_id,
+        unshield_info,
+        unshield_maker,
+        unshield_token_account,
+        vault_account,
+        incognito_proxy,
+        bump_seed,
+        token_mint,
+        payer,
+    )
+    .accounts;
+    assert_eq!(instruction.accounts.len(), 16);
+    assert_eq!(instruction.accounts[0].pubkey, proof_buffer);
+    assert!(instruction.accounts[0].is_writable);
+    assert_eq!(instruction.accounts[1].pubkey, authority);
+    assert!(instruction.accounts[1].is_signer && instruction.accounts[1].is_writable);
+    assert_eq!(instruction.accounts[2..], unshield_accounts[..]);
+    assert_eq!(instruction.data, BridgeInstruction::UnshieldFromBuffer.pack());
+}
+
+#[test]
+fn test_unshield_verified_accounts() {
+    let program_id = Pubkey::new_unique();
+    let incognito_proxy = Pubkey::new_unique();
+    let (_, bump_seed) = Pubkey::find_program_address(&[incognito_proxy.as_ref()], &program_id);
+    let unshield_info = VerifiedUnshieldRequest {
+        inst: [0; 162],
+        height: 7,
+        inst_paths: vec![[1; 32]],
+        inst_path_is_lefts: vec![false],
+        inst_root: [2; 32],
+    };
+
+    let instruction = unshield_verified(
+        program_id,
+        unshield_info.clone(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        incognito_proxy,
+        bump_seed,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let (verified_root, _) =
+        Pubkey::find_program_address(&[VERIFIED_ROOT_SEED, incognito_proxy.as_ref(), &[2; 32]], &program_id);
+    assert_eq!(instruction.accounts.len(), 15);
+    assert_eq!(instruction.accounts[14].pubkey, verified_root);
+    assert!(!instruction.accounts[14].is_writable);
+    assert_eq!(
+        BridgeInstruction::unpack(&instruction.data).unwrap(),
+        BridgeInstruction::UnshieldVerified { unshield_info }
+    );
+}
+
+#[test]
+fn test_unshield_and_call_accounts() {
+    let program_id = Pubkey::new_unique();
+    let incognito_proxy = Pubkey::new_unique();
+    let unshield_maker = Pubkey::new_unique();
+    let token_mint = Pubkey::new_unique();
+    let program_dest = Pubkey::new_unique();
+    let (_, bump_seed) = Pubkey::find_program_address(&[incognito_proxy.as_ref()], &program_id);
+    let (signer_authority, _) = find_signer_authority_address(&program_id, &unshield_maker);
+    let unshield_token_account = get_associated_token_address(&signer_authority, &token_mint);
+    let mut inst = [0; 162];
+    inst[0] = 158;
+    let unshield_info = UnshieldRequest {
+        inst,
+        height: 1,
+        inst_paths: Vec::new(),
+        inst_path_is_lefts: Vec::new(),
+        inst_root: [0; 32],
+        blk_data: [0; 32],
+        indexes: Vec::new(),
+        signatures: Vec::new(),
+    };
+
+    let instruction = unshield_and_call(
+        program_id,
+        unshield_info.clone(),
+        unshield_maker,
+        Pubkey::new_unique(),
+        incognito_proxy,
+        bump_seed,
+        token_mint,
+        Pubkey::new_unique(),
+        program_dest,
+        vec![AccountMeta::new(unshield_token_account, false), AccountMeta::new_readonly(signer_authority, true)],
+        vec![1, 2],
+    );
+    assert_eq!(instruction.accounts.len(), 20);
+    assert!(instruction.accounts[1].is_signer);
+    assert_eq!(instruction.accounts[6].pubkey, unshield_token_account);
+    assert_eq!(instruction.accounts[10].pubkey, signer_authority);
+    assert_eq!(instruction.accounts[14].pubkey, unshield_maker);
+    assert!(!instruction.accounts[16].is_signer);
+    assert_eq!(instruction.accounts[17].pubkey, program_dest);
+    assert_eq!(instruction.accounts[18].pubkey, incognito_proxy);
+    assert_eq!(
+        BridgeInstruction::unpack(&instruction.data).unwrap(),
+        BridgeInstruction::UnshieldAndCall {
+            unshield_info,
+            dapp_request: DappRequest { inst: vec![1, 2], num_acc: 2, sign_index: 1, ..DappRequest::default() },
+        }
+    );
+}
+
+#[test]
+fn test_unpack_init_beacon_default_quorum_threshold() {
+    let vault = Pubkey::new_unique();
+    let mut packed = vec![2];
+    packed.extend_from_slice(vault.as_ref());
+    packed.extend_from_slice(&[255, 0]);
+
+    match BridgeInstruction::unpack(&packed).unwrap() {
+        BridgeInstruction::InitBeacon { init_beacon_info } => {
+            assert_eq!(init_beacon_info.quorum_threshold, QuorumThreshold { numerator: 2, denominator: 3 });
+        }
+        _ => panic!("expected InitBeacon"),
+    }
+    packed.push(1);
+    assert_eq!(BridgeInstruction::unpack(&packed).unwrap_err(), BridgeError::TrailingInstructionData.into());
+
+    // the threshold is always present under tag 24
+    packed[0] = 24;
+    assert_eq!(BridgeInstruction::unpack(&packed).unwrap_err(), BridgeError::InstructionUnpackError.into());
+    packed.push(3);
+    match BridgeInstruction::unpack(&packed).unwrap() {
+        BridgeInstruction::InitBeacon { init_beacon_info } => {
+            assert_eq!(init_beacon_info.quorum_threshold, QuorumThreshold { numerator: 1, denominator: 3 });
+        }
+        _ => panic!("expected InitBeacon"),
+    }
+}
@@ -0,0 +1,177 @@
+mod helpers;
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+use solana_bridge::{
+    error::BridgeError,
+    instruction::{find_allowlist_address, BridgeInstruction},
+    processor::process_instruction,
+    state::{BatchDappRequest, DappCall, DappRequest, IncognitoProxy},
+};
+
+use crate::helpers::fixtures::{allowlist_data, proxy_data, proxy_info};
+
+fn process_set_paused(
+    program_id: &Pubkey,
+    data: &mut [u8],
+    is_writable: bool,
+    guardian: &Pubkey,
+    is_signer: bool,
+    instruction: BridgeInstruction,
+) -> ProgramResult {
+    let key = Pubkey::new_unique();
+    let mut proxy_lamports = 0;
+    let mut guardian_lamports = 0;
+    let mut guardian_data = vec![];
+    let system_program = Pubkey::default();
+    let incognito_proxy = AccountInfo::new(&key, false, is_writable, &mut proxy_lamports, data, program_id, false, 0);
+    let guardian = AccountInfo::new(
+        guardian, is_signer, false, &mut guardian_lamports, &mut guardian_data, &system_program, false, 0,
+    );
+    process_instruction(program_id, &[incognito_proxy, guardian], &instruction.pack())
+}
+
+#[test]
+fn test_pause_and_unpause() {
+    let program_id = Pubkey::new_unique();
+    let guardian = Pubkey::new_unique();
+    let mut data = proxy_data(IncognitoProxy { guardian, ..proxy_info() });
+
+    assert_eq!(process_set_paused(&program_id, &mut data, true, &guardian, true, BridgeInstruction::Pause), Ok(()));
+    assert!(IncognitoProxy::unpack(&data).unwrap().is_paused);
+
+    assert_eq!(process_set_paused(&program_id, &mut data, true, &guardian, true, BridgeInstruction::Unpause), Ok(()));
+    assert!(!IncognitoProxy::unpack(&data).unwrap().is_paused);
+}
+
+#[test]
+fn test_pause_invalid_guardian() {
+    let program_id = Pubkey::new_unique();
+    let guardian = Pubkey::new_unique();
+    let mut data = proxy_data(IncognitoProxy { guardian, ..proxy_info() });
+
+    assert_eq!(
+        process_set_paused(&program_id, &mut data, true, &Pubkey::new_unique(), true, BridgeInstruction::Pause),
+        Err(BridgeError::InvalidGuardian.into())
+    );
+    assert_eq!(
+        process_set_paused(&program_id, &mut data, true, &guardian, false, BridgeInstruction::Pause),
+        Err(BridgeError::InvalidGuardian.into())
+    );
+    assert!(!IncognitoProxy::unpack(&data).unwrap().is_paused);
+}
+
+#[test]
+fn test_pause_proxy_not_writable() {
+    let program_id = Pubkey::new_unique();
+    let guardian = Pubkey::new_unique();
+    let mut data = proxy_data(IncognitoProxy { guardian, ..proxy_info() });
+
+    assert_eq!(
+        process_set_paused(&program_id, &mut data, false, &guardian, true, BridgeInstruction::Pause),
+        Err(BridgeError::AccountNotWritable.into())
+    );
+    assert!(!IncognitoProxy::unpack(&data).unwrap().is_paused);
+}
+
+#[test]
+fn test_shield_while_paused() {
+    let program_id = Pubkey::new_unique();
+    let mut data = proxy_data(IncognitoProxy { is_paused: true, ..proxy_info() });
+    let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    let mut lamports = [0; 5];
+    let mut token_data = [vec![], vec![], vec![], vec![]];
+    let [proxy_lamports, lamports @ ..] = &mut lamports;
+    let [data_0, data_1, data_3, data_4] = &mut token_data;
+    let [lamports_0, lamports_1, lamports_3, lamports_4] = lamports;
+    let loader = Pubkey::default();
+
+    let accounts = [
+        AccountInfo::new(&keys[0], false, true, lamports_0, data_0, &spl_token::ID, false, 0),
+        AccountInfo::new(&keys[1], false, true, lamports_1, data_1, &spl_token::ID, false, 0),
+        AccountInfo::new(&keys[2], false, false, proxy_lamports, &mut data, &program_id, false, 0),
+        AccountInfo::new(&keys[3], true, false, lamports_3, data_3, &spl_token::ID, false, 0),
+        AccountInfo::new(&spl_token::ID, false, false, lamports_4, data_4, &loader, true, 0),
+    ];
+
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &accounts,
+            &BridgeInstruction::Shield { amount: 1, inc_address: [1; 148] }.pack(),
+        ),
+        Err(BridgeError::BridgePaused.into())
+    );
+}
+
+// dapp interaction, or batch of one call, to `program` with the proxy `proxy_key`
+fn process_dapp_interaction(
+    program_id: &Pubkey,
+    proxy_key: &Pubkey,
+    proxy_data: &mut [u8],
+    allowlist_data: &mut [u8],
+    program: &Pubkey,
+    is_batch: bool,
+) -> ProgramResult {
+    let signer = Pubkey::new_unique();
+    let (allowlist_key, _) = find_allowlist_address(program_id);
+    let mut lamports = [0; 4];
+    let mut empty_data = [vec![], vec![]];
+    let [signer_lamports, dest_lamports, proxy_lamports, allowlist_lamports] = &mut lamports;
+    let [signer_data, dest_data] = &mut empty_data;
+    let system_program = Pubkey::default();
+    // the program is the destination of a dapp interaction and the only forwarded account of a batch
+    let accounts = [
+        AccountInfo::new(&signer, true, false, signer_lamports, signer_data, &system_program, false, 0),
+        AccountInfo::new(program, false, false, dest_lamports, dest_data, &system_program, true, 0),
+        AccountInfo::new(proxy_key, false, false, proxy_lamports, proxy_data, program_id, false, 0),
+        AccountInfo::new(&allowlist_key, false, false, allowlist_lamports, allowlist_data, program_id, false, 0),
+    ];
+    let instruction = if is_batch {
+        BridgeInstruction::BatchDappInteraction {
+            batch_request: BatchDappRequest {
+                calls: vec![DappCall { program_index: 0, account_ranges: vec![], inst: vec![1] }],
+                num_acc: 1,
+                sign_index: 0,
+            },
+        }
+    } else {
+        BridgeInstruction::DappInteraction {
+            dapp_request: DappRequest { inst: vec![1], num_acc: 0, sign_index: 0, ..DappRequest::default() },
+        }
+    };
+    process_instruction(program_id, &accounts, &instruction.pack())
+}
+
+#[test]
+fn test_dapp_interaction_foreign_proxy_while_paused() {
+    let program_id = Pubkey::new_unique();
+    let proxy_key = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let mut allowlist_data = allowlist_data(proxy_key, vec![program]);
+    let foreign_proxy = Pubkey::new_unique();
+
+    for is_batch in [false, true] {
+        let mut data = proxy_data(IncognitoProxy { is_paused: true, ..proxy_info() });
+        assert_eq!(
+            process_dapp_interaction(&program_id, &proxy_key, &mut data, &mut allowlist_data, &program, is_batch),
+            Err(BridgeError::BridgePaused.into())
+        );
+        // an unpaused proxy initialized by anyone does not bypass the pause
+        let mut foreign_data = proxy_data(proxy_info());
+        assert_eq!(
+            process_dapp_interaction(
+                &program_id,
+                &foreign_proxy,
+                &mut foreign_data,
+                &mut allowlist_data,
+                &program,
+                is_batch,
+            ),
+            Err(BridgeError::InvalidAllowlistAccount.into())
+        );
+    }
+}
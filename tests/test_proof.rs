@@ -0,0 +1,95 @@
+mod helpers;
+
+use proptest::{collection::vec, prelude::*};
+use solana_program::keccak::hash;
+use solana_bridge::proof::{
+    append_at_top, beacon_block_hash, instruction_hash, instruction_in_merkle_tree, merkle_root, InstructionProof,
+};
+
+use crate::helpers::beacon::{inst_leaf, u256};
+
+fn leaves(len: usize) -> Vec<[u8; 32]> {
+    (0..len).map(|i| hash(&[i as u8]).to_bytes()).collect()
+}
+
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut data = left.to_vec();
+    data.extend_from_slice(right);
+    hash(&data).to_bytes()
+}
+
+#[test]
+fn test_single_leaf() {
+    let leaves = leaves(1);
+    let proof = InstructionProof::build(&leaves, 0).unwrap();
+    assert_eq!(proof, InstructionProof::default());
+    assert_eq!(merkle_root(&leaves), leaves[0]);
+    assert!(proof.verify(&leaves[0], &leaves[0]));
+    assert_eq!(merkle_root(&[]), [0; 32]);
+    assert_eq!(InstructionProof::build(&[], 0), None);
+}
+
+#[test]
+fn test_odd_leaves() {
+    let leaves = leaves(3);
+    let root = hash_pair(&hash_pair(&leaves[0], &leaves[1]), &hash_pair(&leaves[2], &leaves[2]));
+    assert_eq!(merkle_root(&leaves), root);
+
+    // the last leaf has no sibling and is hashed with itself
+    let proof = InstructionProof::build(&leaves, 2).unwrap();
+    assert_eq!(proof.inst_paths, vec![[0; 32], hash_pair(&leaves[0], &leaves[1])]);
+    assert_eq!(proof.inst_path_is_lefts, vec![false, true]);
+    assert!(proof.verify(&leaves[2], &root));
+    assert_eq!(InstructionProof::build(&leaves, 3), None);
+}
+
+#[test]
+fn test_every_leaf_of_tree() {
+    for len in 1..=17 {
+        let leaves = leaves(len);
+        let root = merkle_root(&leaves);
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = InstructionProof::build(&leaves, index).unwrap();
+            assert_eq!(proof.root(leaf), Some(root));
+            assert!(instruction_in_merkle_tree(leaf, &root, &proof.inst_paths, &proof.inst_path_is_lefts));
+            if len > 1 {
+                assert!(!proof.verify(&leaves[(index + 1) % len], &root));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_mismatched_path_lengths() {
+    let leaves = leaves(4);
+    let mut proof = InstructionProof::build(&leaves, 1).unwrap();
+    proof.inst_path_is_lefts.pop();
+    assert_eq!(proof.root(&leaves[1]), None);
+    assert!(!proof.verify(&leaves[1], &merkle_root(&leaves)));
+}
+
+#[test]
+fn test_instruction_and_block_hash() {
+    assert_eq!(append_at_top(0x0102), u256(0x0102));
+    let inst = [7; 162];
+    assert_eq!(instruction_hash(&inst, 42), inst_leaf(&inst, 42));
+
+    let mut blk = [1; 32].to_vec();
+    blk.extend_from_slice(&[2; 32]);
+    assert_eq!(beacon_block_hash(&[1; 32], &[2; 32]), hash(&hash(&blk).to_bytes()).to_bytes());
+}
+
+proptest! {
+    #[test]
+    fn test_build_verify(
+        data in vec(any::<[u8; 32]>(), 1..64),
+        index in any::<prop::sample::Index>(),
+        other in any::<[u8; 32]>(),
+    ) {
+        let index = index.index(data.len());
+        let root = merkle_root(&data);
+        let proof = InstructionProof::build(&data, index).unwrap();
+        prop_assert!(proof.verify(&data[index], &root));
+        prop_assert_eq!(proof.verify(&other, &root), other == data[index]);
+    }
+}
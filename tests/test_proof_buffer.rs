@@ -0,0 +1,213 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+    sysvar,
+};
+use solana_bridge::{
+    error::BridgeError,
+    instruction::{close_proof_buffer, write_proof_chunks, BridgeInstruction},
+    processor::process_instruction,
+    state::{ProofBuffer, UnshieldRequest, PROOF_BUFFER_ACCOUNT_TYPE},
+};
+
+fn unshield_request() -> UnshieldRequest {
+    UnshieldRequest {
+        inst: [7; 162],
+        height: 100,
+        inst_paths: vec![[1; 32]; 12],
+        inst_path_is_lefts: vec![true; 12],
+        inst_root: [2; 32],
+        blk_data: [3; 32],
+        indexes: (0..14).collect(),
+        signatures: vec![[4; 65]; 14],
+    }
+}
+
+fn process_init(program_id: &Pubkey, authority: &Pubkey, proof_buffer_data: &mut [u8]) -> ProgramResult {
+    let rent = Rent::default();
+    let mut rent_data = rent.lamports_per_byte_year.to_le_bytes().to_vec();
+    rent_data.extend_from_slice(&rent.exemption_threshold.to_le_bytes());
+    rent_data.push(rent.burn_percent);
+    let keys = [sysvar::rent::id(), Pubkey::new_unique()];
+    let mut lamports = [0, u32::MAX as u64, 0];
+    let mut authority_data = vec![];
+    let [rent_lamports, proof_buffer_lamports, authority_lamports] = &mut lamports;
+    let system_owner = system_program::id();
+    let accounts = [
+        AccountInfo::new(&keys[0], false, false, rent_lamports, &mut rent_data, &system_owner, false, 0),
+        AccountInfo::new(&keys[1], false, true, proof_buffer_lamports, proof_buffer_data, program_id, false, 0),
+        AccountInfo::new(authority, true, false, authority_lamports, &mut authority_data, &system_owner, false, 0),
+    ];
+    process_instruction(program_id, &accounts, &BridgeInstruction::InitProofBuffer.pack())
+}
+
+fn process_with_buffer(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    proof_buffer_data: &mut [u8],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let proof_buffer = Pubkey::new_unique();
+    let mut proof_buffer_lamports = u32::MAX as u64;
+    let mut authority_lamports = 0;
+    let mut authority_data = vec![];
+    let system_owner = system_program::id();
+    let accounts = [
+        AccountInfo::new(&proof_buffer, false, true, &mut proof_buffer_lamports, proof_buffer_data, program_id, false, 0),
+        AccountInfo::new(authority, true, true, &mut authority_lamports, &mut authority_data, &system_owner, false, 0),
+    ];
+    process_instruction(program_id, &accounts, instruction_data)
+}
+
+#[test]
+fn test_write_proof_in_chunks() {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let unshield_info = unshield_request();
+    let proof = BridgeInstruction::pack_unshield_proof(&unshield_info);
+    assert!(proof.len() > 1232);
+    let mut data = vec![0; ProofBuffer::space(proof.len())];
+
+    assert_eq!(process_init(&program_id, &authority, &mut data), Ok(()));
+    assert_eq!(
+        process_init(&program_id, &authority, &mut data),
+        Err(BridgeError::AccInitialized.into())
+    );
+    assert_eq!(
+        ProofBuffer::unpack(&data[..ProofBuffer::LEN]).unwrap(),
+        ProofBuffer { is_initialized: true, authority }
+    );
+    assert_eq!(data[0], PROOF_BUFFER_ACCOUNT_TYPE);
+
+    // chunks may arrive in any order
+    let chunks = write_proof_chunks(program_id, Pubkey::new_unique(), authority, &unshield_info, 900);
+    assert_eq!(chunks.len(), 2);
+    for chunk in chunks.iter().rev() {
+        assert_eq!(process_with_buffer(&program_id, &authority, &mut data, &chunk.data), Ok(()));
+    }
+    assert_eq!(
+        BridgeInstruction::unpack_unshield_proof(&data[ProofBuffer::LEN..]).unwrap(),
+        unshield_info
+    );
+}
+
+#[test]
+fn test_write_proof_chunk_out_of_bounds() {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let mut data = vec![0; ProofBuffer::space(100)];
+    assert_eq!(process_init(&program_id, &authority, &mut data), Ok(()));
+
+    let chunk = BridgeInstruction::WriteProofChunk { offset: 60, data: vec![1; 41] }.pack();
+    assert_eq!(
+        process_with_buffer(&program_id, &authority, &mut data, &chunk),
+        Err(BridgeError::ProofChunkOutOfBounds.into())
+    );
+    let chunk = BridgeInstruction::WriteProofChunk { offset: u32::MAX, data: vec![1] }.pack();
+    assert_eq!(
+        process_with_buffer(&program_id, &authority, &mut data, &chunk),
+        Err(BridgeError::ProofChunkOutOfBounds.into())
+    );
+    let chunk = BridgeInstruction::WriteProofChunk { offset: 60, data: vec![1; 40] }.pack();
+    assert_eq!(process_with_buffer(&program_id, &authority, &mut data, &chunk), Ok(()));
+}
+
+#[test]
+fn test_proof_buffer_authority() {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let mut data = vec![0; ProofBuffer::space(100)];
+
+    let chunk = BridgeInstruction::WriteProofChunk { offset: 0, data: vec![1; 10] }.pack();
+    assert_eq!(
+        process_with_buffer(&program_id, &authority, &mut data, &chunk),
+        Err(ProgramError::UninitializedAccount)
+    );
+    assert_eq!(process_init(&program_id, &authority, &mut data), Ok(()));
+    for instruction_data in [
+        chunk,
+        BridgeInstruction::UnshieldFromBuffer.pack(),
+        BridgeInstruction::CloseProofBuffer.pack(),
+    ] {
+        assert_eq!(
+            process_with_buffer(&program_id, &Pubkey::new_unique(), &mut data, &instruction_data),
+            Err(BridgeError::InvalidProofBufferAuthority.into())
+        );
+    }
+}
+
+#[test]
+fn test_unshield_from_incomplete_buffer() {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let proof = BridgeInstruction::pack_unshield_proof(&unshield_request());
+    let mut data = vec![0; ProofBuffer::space(proof.len())];
+    assert_eq!(process_init(&program_id, &authority, &mut data), Ok(()));
+
+    let chunk = BridgeInstruction::WriteProofChunk { offset: 0, data: proof[..100].to_vec() }.pack();
+    assert_eq!(process_with_buffer(&program_id, &authority, &mut data, &chunk), Ok(()));
+    assert_eq!(
+        process_with_buffer(&program_id, &authority, &mut data, &BridgeInstruction::UnshieldFromBuffer.pack()),
+        Err(BridgeError::TrailingInstructionData.into())
+    );
+    // the buffer is kept until the unshield succeeds
+    assert!(ProofBuffer::unpack(&data[..ProofBuffer::LEN]).unwrap().is_initialized);
+}
+
+#[test]
+fn test_init_proof_buffer_requires_zeroed_account() {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+
+    // another account of the program holding data
+    let mut data = vec![0; ProofBuffer::space(100)];
+    data[ProofBuffer::LEN + 50] = 1;
+    assert_eq!(process_init(&program_id, &authority, &mut data), Err(ProgramError::InvalidAccountData));
+    data[ProofBuffer::LEN + 50] = 0;
+    data[0] = 1;
+    assert_eq!(process_init(&program_id, &authority, &mut data), Err(ProgramError::InvalidAccountData));
+
+    // written or used only as a proof buffer
+    let chunk = BridgeInstruction::WriteProofChunk { offset: 0, data: vec![1; 10] }.pack();
+    data[1..ProofBuffer::LEN].copy_from_slice(authority.as_ref());
+    assert_eq!(
+        process_with_buffer(&program_id, &authority, &mut data, &chunk),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn test_close_proof_buffer() {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let proof_buffer = Pubkey::new_unique();
+    let mut data = vec![0; ProofBuffer::space(100)];
+    assert_eq!(process_init(&program_id, &authority, &mut data), Ok(()));
+    let chunk = BridgeInstruction::WriteProofChunk { offset: 0, data: vec![1; 10] }.pack();
+    assert_eq!(process_with_buffer(&program_id, &authority, &mut data, &chunk), Ok(()));
+
+    let instruction = close_proof_buffer(program_id, proof_buffer, authority);
+    assert!(instruction.accounts[1].is_signer && instruction.accounts[1].is_writable);
+    let mut proof_buffer_lamports = 1_000;
+    let mut authority_lamports = 5;
+    let mut authority_data = vec![];
+    let system_owner = system_program::id();
+    {
+        let accounts = [
+            AccountInfo::new(&proof_buffer, false, true, &mut proof_buffer_lamports, &mut data, &program_id, false, 0),
+            AccountInfo::new(&authority, true, true, &mut authority_lamports, &mut authority_data, &system_owner, false, 0),
+        ];
+        assert_eq!(process_instruction(&program_id, &accounts, &instruction.data), Ok(()));
+    }
+    assert_eq!((proof_buffer_lamports, authority_lamports), (0, 1_005));
+    assert!(data.iter().all(|byte| *byte == 0));
+    assert_eq!(
+        process_with_buffer(&program_id, &authority, &mut data, &instruction.data),
+        Err(ProgramError::UninitializedAccount)
+    );
+}
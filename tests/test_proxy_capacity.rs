@@ -0,0 +1,310 @@
+mod helpers;
+
+use solana_program::{
+    account_info::AccountInfo,
+    bpf_loader_upgradeable,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+    sysvar,
+};
+use solana_bridge::{
+    error::BridgeError,
+    instruction::BridgeInstruction,
+    processor::process_instruction,
+    state::{IncognitoProxy, InitBeaconRequest, QuorumThreshold, LEGACY_PROXY_VERSION, PROXY_VERSION},
+};
+
+use crate::helpers::{beacon::Committee, fixtures::program_data};
+
+fn incognito_proxy(committee: &Committee, guardian: Pubkey) -> IncognitoProxy {
+    IncognitoProxy {
+        is_initialized: true,
+        bump_seed: 255,
+        vault: Pubkey::new_unique(),
+        start_height: 0,
+        guardian,
+        is_paused: false,
+        quorum_threshold: QuorumThreshold::default(),
+        threshold_height: 0,
+        beacons: committee.beacons(),
+    }
+}
+
+fn rent_data() -> Vec<u8> {
+    let rent = Rent::default();
+    let mut rent_data = rent.lamports_per_byte_year.to_le_bytes().to_vec();
+    rent_data.extend_from_slice(&rent.exemption_threshold.to_le_bytes());
+    rent_data.push(rent.burn_percent);
+    rent_data
+}
+
+// unversioned proxy as created before the layout was versioned
+fn legacy_proxy_data(vault: &Pubkey, committee: &Committee) -> Vec<u8> {
+    let mut data = vec![1, 254];
+    data.extend_from_slice(vault.as_ref());
+    data.push(committee.beacons().len() as u8);
+    for beacon in committee.beacons() {
+        data.extend_from_slice(&beacon.to_bytes());
+    }
+    data.resize(IncognitoProxy::LEGACY_LEN, 0);
+    data
+}
+
+// account data as the runtime serializes it, its length before it and room to grow after it
+fn serialized_data(data: &[u8]) -> Vec<u8> {
+    let mut serialized = (data.len() as u64).to_le_bytes().to_vec();
+    serialized.extend_from_slice(data);
+    serialized.resize(serialized.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+    serialized
+}
+
+// account data of a serialized account, with its current length
+fn account_data(serialized: &[u8]) -> &[u8] {
+    let (data_len, data) = serialized.split_at(8);
+    &data[..u64::from_le_bytes(data_len.try_into().unwrap()) as usize]
+}
+
+fn process_init_beacon(program_id: &Pubkey, proxy_data: &mut [u8], beacons: &Committee) -> ProgramResult {
+    let mut rent_data = rent_data();
+    let keys = [sysvar::rent::id(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    let mut lamports = [0, u32::MAX as u64, u32::MAX as u64, 0];
+    let mut vault_data = vec![0; 64];
+    let mut guardian_data = vec![];
+    let [rent_lamports, proxy_lamports, vault_lamports, guardian_lamports] = &mut lamports;
+    let system_owner = system_program::id();
+    let accounts = [
+        AccountInfo::new(&keys[0], false, false, rent_lamports, &mut rent_data, &system_owner, false, 0),
+        AccountInfo::new(&keys[1], false, true, proxy_lamports, proxy_data, program_id, false, 0),
+        AccountInfo::new(&keys[2], false, true, vault_lamports, &mut vault_data, program_id, false, 0),
+        AccountInfo::new(&keys[3], true, false, guardian_lamports, &mut guardian_data, &system_owner, false, 0),
+    ];
+    process_instruction(
+        program_id,
+        &accounts,
+        &BridgeInstruction::InitBeacon {
+            init_beacon_info: InitBeaconRequest {
+                bump_seed: 255,
+                vault: keys[2],
+                beacons: beacons.beacons(),
+                ..InitBeaconRequest::default()
+            },
+        }
+        .pack(),
+    )
+}
+
+#[test]
+fn test_pack_unpack_capacity() {
+    for (capacity, size) in [(4, 4), (4, 0), (40, 33), (255, 255)] {
+        let committee = Committee::new(size, 1);
+        let mut data = vec![0; IncognitoProxy::space(capacity)];
+        assert_eq!(IncognitoProxy::capacity(data.len()), capacity);
+        IncognitoProxy::pack(incognito_proxy(&committee, Pubkey::new_unique()), &mut data).unwrap();
+        assert_eq!(data[1], PROXY_VERSION);
+        assert!(IncognitoProxy::unpack(&data).unwrap().beacons == committee.beacons());
+    }
+}
+
+#[test]
+fn test_pack_exceeds_capacity() {
+    let committee = Committee::new(5, 1);
+    let mut data = vec![0; IncognitoProxy::space(4)];
+    assert_eq!(
+        IncognitoProxy::pack(incognito_proxy(&committee, Pubkey::new_unique()), &mut data),
+        Err(BridgeError::BeaconCapacityExceeded.into())
+    );
+}
+
+#[test]
+fn test_unpack_invalid_layout() {
+    let committee = Committee::new(4, 1);
+    let mut data = vec![0; IncognitoProxy::space(4)];
+    IncognitoProxy::pack(incognito_proxy(&committee, Pubkey::new_unique()), &mut data).unwrap();
+
+    assert_eq!(
+        IncognitoProxy::unpack(&data[..IncognitoProxy::HEADER_LEN - 1]).unwrap_err(),
+        ProgramError::InvalidAccountData
+    );
+    // beacon length larger than the account holds
+    assert_eq!(
+        IncognitoProxy::unpack(&data[..IncognitoProxy::space(3)]).unwrap_err(),
+        ProgramError::InvalidAccountData
+    );
+    data[1] = PROXY_VERSION + 1;
+    assert_eq!(
+        IncognitoProxy::unpack(&data).unwrap_err(),
+        BridgeError::UnsupportedProxyVersion.into()
+    );
+}
+
+#[test]
+fn test_init_beacon_capacity() {
+    let program_id = Pubkey::new_unique();
+    let mut data = vec![0; IncognitoProxy::space(4)];
+    assert_eq!(
+        process_init_beacon(&program_id, &mut data, &Committee::new(5, 1)),
+        Err(BridgeError::BeaconCapacityExceeded.into())
+    );
+
+    let committee = Committee::new(4, 1);
+    assert_eq!(process_init_beacon(&program_id, &mut data, &committee), Ok(()));
+    assert!(IncognitoProxy::unpack(&data).unwrap().beacons == committee.beacons());
+}
+
+// proxy holding 6 beacons in an account of `space` bytes resized to `capacity`
+fn process_resize_proxy(program_id: &Pubkey, space: usize, capacity: u8) -> ProgramResult {
+    let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), system_program::id()];
+    let mut proxy_data = vec![0; space];
+    IncognitoProxy::pack(incognito_proxy(&Committee::new(6, 1), keys[1]), &mut proxy_data).unwrap();
+    let mut lamports = [u32::MAX as u64, 0, u32::MAX as u64, 0];
+    let mut data = [vec![], vec![], vec![]];
+    let [proxy_lamports, guardian_lamports, payer_lamports, system_lamports] = &mut lamports;
+    let [guardian_data, payer_data, system_data] = &mut data;
+    let system_owner = system_program::id();
+    let loader = Pubkey::new_unique();
+    let accounts = [
+        AccountInfo::new(&keys[0], false, true, proxy_lamports, &mut proxy_data, program_id, false, 0),
+        AccountInfo::new(&keys[1], true, false, guardian_lamports, guardian_data, &system_owner, false, 0),
+        AccountInfo::new(&keys[2], true, true, payer_lamports, payer_data, &system_owner, false, 0),
+        AccountInfo::new(&keys[3], false, false, system_lamports, system_data, &loader, true, 0),
+    ];
+
+    process_instruction(program_id, &accounts, &BridgeInstruction::ResizeProxy { capacity }.pack())
+}
+
+#[test]
+fn test_resize_proxy_below_beacons() {
+    assert_eq!(
+        process_resize_proxy(&Pubkey::new_unique(), IncognitoProxy::space(8), 5),
+        Err(BridgeError::BeaconCapacityExceeded.into())
+    );
+}
+
+#[test]
+fn test_resize_proxy_growth_limit() {
+    let program_id = Pubkey::new_unique();
+    let space = IncognitoProxy::space(32);
+    assert_eq!(
+        process_resize_proxy(&program_id, space, 255),
+        Err(BridgeError::ProxyGrowthExceeded.into())
+    );
+
+    // growing in steps within the limit passes validation and reaches the rent sysvar, unavailable natively
+    let capacity = 32 + (MAX_PERMITTED_DATA_INCREASE / 64) as u8;
+    assert_eq!(IncognitoProxy::space(capacity as usize), space + MAX_PERMITTED_DATA_INCREASE);
+    assert_eq!(process_resize_proxy(&program_id, space, capacity), Err(ProgramError::UnsupportedSysvar));
+    assert_eq!(
+        process_resize_proxy(&program_id, space, capacity + 1),
+        Err(BridgeError::ProxyGrowthExceeded.into())
+    );
+}
+
+#[test]
+fn test_unpack_legacy_proxy() {
+    let vault = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    let mut data = legacy_proxy_data(&vault, &committee);
+    assert_eq!(IncognitoProxy::version(&data), Ok(LEGACY_PROXY_VERSION));
+
+    let incognito_proxy_info = IncognitoProxy::unpack(&data).unwrap();
+    assert_eq!(incognito_proxy_info.bump_seed, 254);
+    assert_eq!(incognito_proxy_info.vault, vault);
+    assert!(incognito_proxy_info.beacons == committee.beacons());
+    assert_eq!(incognito_proxy_info.guardian, Pubkey::default());
+    assert_eq!(incognito_proxy_info.quorum_threshold, QuorumThreshold::default());
+    assert!(!incognito_proxy_info.is_paused);
+
+    // the unversioned layout is read only until migrated
+    assert_eq!(
+        IncognitoProxy::pack(incognito_proxy_info, &mut data),
+        Err(BridgeError::UnsupportedProxyVersion.into())
+    );
+    data[34] = IncognitoProxy::LEGACY_CAPACITY as u8 + 1;
+    assert_eq!(IncognitoProxy::unpack(&data).unwrap_err(), ProgramError::InvalidAccountData);
+}
+
+// migrate the serialized proxy signed by `signer`, the upgrade authority of the program is `upgrade_authority`
+fn process_migrate_proxy(
+    program_id: &Pubkey,
+    serialized_proxy: &mut [u8],
+    guardian: &Pubkey,
+    upgrade_authority: &Pubkey,
+    signer: &Pubkey,
+) -> ProgramResult {
+    let (data_len, proxy_data) = serialized_proxy.split_at_mut(8);
+    let proxy_data = &mut proxy_data[..u64::from_le_bytes((&*data_len).try_into().unwrap()) as usize];
+    let (program_data_key, _) = Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    let keys = [
+        sysvar::rent::id(),
+        Pubkey::new_unique(),
+        *guardian,
+        *signer,
+        program_data_key,
+        Pubkey::new_unique(),
+        system_program::id(),
+    ];
+    // enough lamports for the grown account, no transfer needed
+    let mut lamports = [0, u32::MAX as u64, 0, 0, 0, 0, 0];
+    let mut data = [rent_data(), vec![], vec![], program_data(upgrade_authority), vec![], vec![]];
+    let [
+        rent_lamports,
+        proxy_lamports,
+        guardian_lamports,
+        signer_lamports,
+        program_data_lamports,
+        payer_lamports,
+        system_lamports,
+    ] = &mut lamports;
+    let [rent_data, guardian_data, signer_data, program_data, payer_data, system_data] = &mut data;
+    let system_owner = system_program::id();
+    let loader = bpf_loader_upgradeable::id();
+    let accounts = [
+        AccountInfo::new(&keys[0], false, false, rent_lamports, rent_data, &system_owner, false, 0),
+        AccountInfo::new(&keys[1], false, true, proxy_lamports, proxy_data, program_id, false, 0),
+        AccountInfo::new(&keys[2], true, false, guardian_lamports, guardian_data, &system_owner, false, 0),
+        AccountInfo::new(&keys[3], true, false, signer_lamports, signer_data, &system_owner, false, 0),
+        AccountInfo::new(&keys[4], false, false, program_data_lamports, program_data, &loader, false, 0),
+        AccountInfo::new(&keys[5], true, true, payer_lamports, payer_data, &system_owner, false, 0),
+        AccountInfo::new(&keys[6], false, false, system_lamports, system_data, &loader, true, 0),
+    ];
+    process_instruction(program_id, &accounts, &BridgeInstruction::MigrateProxy.pack())
+}
+
+#[test]
+fn test_migrate_legacy_proxy() {
+    let program_id = Pubkey::new_unique();
+    let guardian = Pubkey::new_unique();
+    let upgrade_authority = Pubkey::new_unique();
+    let vault = Pubkey::new_unique();
+    let committee = Committee::new(20, 1);
+    let mut serialized_proxy = serialized_data(&legacy_proxy_data(&vault, &committee));
+
+    assert_eq!(
+        process_migrate_proxy(&program_id, &mut serialized_proxy, &guardian, &upgrade_authority, &guardian),
+        Err(BridgeError::InvalidUpgradeAuthority.into())
+    );
+    assert_eq!(IncognitoProxy::version(account_data(&serialized_proxy)), Ok(LEGACY_PROXY_VERSION));
+
+    assert_eq!(
+        process_migrate_proxy(&program_id, &mut serialized_proxy, &guardian, &upgrade_authority, &upgrade_authority),
+        Ok(())
+    );
+    let data = account_data(&serialized_proxy);
+    assert_eq!(data.len(), IncognitoProxy::space(IncognitoProxy::LEGACY_CAPACITY));
+    assert_eq!(IncognitoProxy::version(data), Ok(PROXY_VERSION));
+    let incognito_proxy_info = IncognitoProxy::unpack(data).unwrap();
+    assert_eq!(incognito_proxy_info.bump_seed, 254);
+    assert_eq!(incognito_proxy_info.vault, vault);
+    assert_eq!(incognito_proxy_info.guardian, guardian);
+    assert_eq!(incognito_proxy_info.quorum_threshold, QuorumThreshold::default());
+    assert!(incognito_proxy_info.beacons == committee.beacons());
+
+    // a migrated proxy cannot be migrated again to replace its guardian
+    assert_eq!(
+        process_migrate_proxy(&program_id, &mut serialized_proxy, &guardian, &upgrade_authority, &upgrade_authority),
+        Err(BridgeError::UnsupportedProxyVersion.into())
+    );
+}
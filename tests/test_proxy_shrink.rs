@@ -0,0 +1,73 @@
+mod helpers;
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE, SUCCESS},
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+use solana_bridge::{instruction::BridgeInstruction, processor::process_instruction, state::IncognitoProxy};
+
+use crate::helpers::{beacon::Committee, fixtures::proxy_info};
+
+// provides the rent sysvar, unavailable to native tests otherwise
+struct RentStubs;
+
+impl SyscallStubs for RentStubs {
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+}
+
+// resize a proxy of `space` bytes holding `lamports` and 6 beacons, the proxy data is serialized
+// as by the runtime, with its length before it and room to grow after it
+fn process_resize_proxy(space: usize, lamports: u64, capacity: u8) -> (ProgramResult, Vec<u8>, u64, u64) {
+    set_syscall_stubs(Box::new(RentStubs));
+    let program_id = Pubkey::new_unique();
+    let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), system_program::id()];
+    let mut serialized = (space as u64).to_le_bytes().to_vec();
+    serialized.resize(8 + space + MAX_PERMITTED_DATA_INCREASE, 0);
+    let proxy = IncognitoProxy { guardian: keys[1], beacons: Committee::new(6, 1).beacons(), ..proxy_info() };
+    IncognitoProxy::pack(proxy, &mut serialized[8..8 + space]).unwrap();
+    let mut lamports = [lamports, 0, 0, 0];
+    let mut data = [vec![], vec![], vec![]];
+    let [proxy_lamports, guardian_lamports, payer_lamports, system_lamports] = &mut lamports;
+    let [guardian_data, payer_data, system_data] = &mut data;
+    let system_owner = system_program::id();
+    let loader = Pubkey::new_unique();
+    let result = {
+        let proxy_data = &mut serialized[8..8 + space];
+        let accounts = [
+            AccountInfo::new(&keys[0], false, true, proxy_lamports, proxy_data, &program_id, false, 0),
+            AccountInfo::new(&keys[1], true, false, guardian_lamports, guardian_data, &system_owner, false, 0),
+            AccountInfo::new(&keys[2], true, true, payer_lamports, payer_data, &system_owner, false, 0),
+            AccountInfo::new(&keys[3], false, false, system_lamports, system_data, &loader, true, 0),
+        ];
+        process_instruction(&program_id, &accounts, &BridgeInstruction::ResizeProxy { capacity }.pack())
+    };
+    let data_len = u64::from_le_bytes(serialized[..8].try_into().unwrap()) as usize;
+    (result, serialized[8..8 + data_len].to_vec(), lamports[0], lamports[2])
+}
+
+#[test]
+fn test_shrink_proxy_refunds_rent() {
+    let rent = Rent::default();
+    let space = IncognitoProxy::space(20);
+    let lamports = rent.minimum_balance(space) + 10;
+
+    let (result, data, proxy_lamports, payer_lamports) = process_resize_proxy(space, lamports, 6);
+    assert_eq!(result, Ok(()));
+    assert_eq!(data.len(), IncognitoProxy::space(6));
+    assert!(IncognitoProxy::unpack(&data).unwrap().beacons == Committee::new(6, 1).beacons());
+    assert_eq!(proxy_lamports, rent.minimum_balance(IncognitoProxy::space(6)));
+    assert_eq!(payer_lamports, lamports - proxy_lamports);
+
+    // a resize to the same size only refunds the rent in excess
+    let (result, data, proxy_lamports, payer_lamports) = process_resize_proxy(space, lamports, 20);
+    assert_eq!(result, Ok(()));
+    assert_eq!(data.len(), space);
+    assert_eq!((proxy_lamports, payer_lamports), (rent.minimum_balance(space), 10));
+}
@@ -0,0 +1,128 @@
+mod helpers;
+
+use solana_program::program_error::ProgramError;
+use solana_bridge::{error::BridgeError, quorum::verify_beacon_signatures, state::QuorumThreshold};
+
+use crate::helpers::beacon::Committee;
+
+const BLK_DATA: [u8; 32] = [1; 32];
+const INST_ROOT: [u8; 32] = [2; 32];
+
+fn verify(committee: &Committee, indexes: &[u8], signatures: &[[u8; 65]]) -> Result<(), ProgramError> {
+    verify_with_threshold(committee, QuorumThreshold::default(), indexes, signatures)
+}
+
+fn verify_with_threshold(
+    committee: &Committee,
+    threshold: QuorumThreshold,
+    indexes: &[u8],
+    signatures: &[[u8; 65]],
+) -> Result<(), ProgramError> {
+    verify_beacon_signatures(&committee.beacons(), threshold, &BLK_DATA, &INST_ROOT, indexes, signatures)
+}
+
+#[test]
+fn test_quorum_success() {
+    let committee = Committee::new(4, 1);
+    let signatures = committee.sign(&BLK_DATA, &INST_ROOT, &[0, 2, 3]);
+    assert_eq!(verify(&committee, &[0, 2, 3], &signatures), Ok(()));
+}
+
+#[test]
+fn test_quorum_duplicate_index() {
+    let committee = Committee::new(4, 1);
+    let signatures = committee.sign(&BLK_DATA, &INST_ROOT, &[0, 0, 0]);
+    assert_eq!(
+        verify(&committee, &[0, 0, 0], &signatures),
+        Err(BridgeError::DuplicateBeaconIndex.into())
+    );
+}
+
+#[test]
+fn test_quorum_unsorted_indexes() {
+    let committee = Committee::new(4, 1);
+    let signatures = committee.sign(&BLK_DATA, &INST_ROOT, &[1, 0, 2]);
+    assert_eq!(
+        verify(&committee, &[1, 0, 2], &signatures),
+        Err(BridgeError::UnsortedBeaconIndexes.into())
+    );
+}
+
+#[test]
+fn test_quorum_index_out_of_range() {
+    let committee = Committee::new(4, 1);
+    let mut signatures = committee.sign(&BLK_DATA, &INST_ROOT, &[0, 1]);
+    signatures.push(signatures[1]);
+    assert_eq!(
+        verify(&committee, &[0, 1, 4], &signatures),
+        Err(BridgeError::BeaconIndexOutOfRange.into())
+    );
+}
+
+#[test]
+fn test_quorum_not_enough_signatures() {
+    let committee = Committee::new(6, 1);
+    let signatures = committee.sign(&BLK_DATA, &INST_ROOT, &[0, 1, 2, 3]);
+    assert_eq!(
+        verify(&committee, &[0, 1, 2, 3], &signatures),
+        Err(BridgeError::InvalidNumberOfSignature.into())
+    );
+}
+
+#[test]
+fn test_quorum_indexes_signatures_mismatch() {
+    let committee = Committee::new(4, 1);
+    let signatures = committee.sign(&BLK_DATA, &INST_ROOT, &[0, 1, 2]);
+    assert_eq!(
+        verify(&committee, &[0, 1, 2, 3], &signatures),
+        Err(BridgeError::InvalidBeaconInstruction.into())
+    );
+}
+
+#[test]
+fn test_quorum_signature_of_other_beacon() {
+    let committee = Committee::new(4, 1);
+    let signatures = committee.sign(&BLK_DATA, &INST_ROOT, &[0, 1, 3]);
+    assert_eq!(
+        verify(&committee, &[0, 1, 2], &signatures),
+        Err(BridgeError::InvalidBeaconSignature.into())
+    );
+}
+
+#[test]
+fn test_quorum_unrecoverable_signature() {
+    let committee = Committee::new(4, 1);
+    let mut signatures = committee.sign(&BLK_DATA, &INST_ROOT, &[0, 1, 2]);
+    signatures[2][64] = 4;
+    assert_eq!(
+        verify(&committee, &[0, 1, 2], &signatures),
+        Err(BridgeError::InvalidBeaconSignature.into())
+    );
+}
+
+#[test]
+fn test_quorum_custom_threshold() {
+    let committee = Committee::new(4, 1);
+    let signatures = committee.sign(&BLK_DATA, &INST_ROOT, &[2]);
+    let any_beacon = QuorumThreshold { numerator: 0, denominator: 1 };
+    assert_eq!(verify_with_threshold(&committee, any_beacon, &[2], &signatures), Ok(()));
+
+    let signatures = committee.sign(&BLK_DATA, &INST_ROOT, &[0, 1]);
+    let half = QuorumThreshold { numerator: 1, denominator: 2 };
+    assert_eq!(
+        verify_with_threshold(&committee, half, &[0, 1], &signatures),
+        Err(BridgeError::InvalidNumberOfSignature.into())
+    );
+    let signatures = committee.sign(&BLK_DATA, &INST_ROOT, &[0, 1, 3]);
+    assert_eq!(verify_with_threshold(&committee, half, &[0, 1, 3], &signatures), Ok(()));
+}
+
+#[test]
+fn test_quorum_zero_denominator_rejects() {
+    let committee = Committee::new(4, 1);
+    let signatures = committee.sign(&BLK_DATA, &INST_ROOT, &[0, 1, 2, 3]);
+    assert_eq!(
+        verify_with_threshold(&committee, QuorumThreshold { numerator: 0, denominator: 0 }, &[0, 1, 2, 3], &signatures),
+        Err(BridgeError::InvalidNumberOfSignature.into())
+    );
+}
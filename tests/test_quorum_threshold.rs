@@ -0,0 +1,161 @@
+mod helpers;
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar,
+};
+use solana_bridge::{
+    error::BridgeError,
+    instruction::BridgeInstruction,
+    processor::process_instruction,
+    state::{IncognitoProxy, InitBeaconRequest, QuorumThreshold, BeaconInstructionRequest, DEFAULT_BEACON_CAPACITY},
+};
+
+use crate::helpers::{
+    beacon::{inst_leaf, u256, Committee},
+    fixtures::{proxy_data, proxy_info},
+};
+
+fn threshold_inst(meta_type: u8, numerator: u64, denominator: u64) -> Vec<u8> {
+    let mut inst = vec![meta_type, 1];
+    inst.extend_from_slice(&u256(numerator));
+    inst.extend_from_slice(&u256(denominator));
+    inst
+}
+
+fn threshold_request(committee: &Committee, indexes: &[u8], inst: Vec<u8>, height: u64) -> BeaconInstructionRequest {
+    let inst_root = inst_leaf(&inst, height);
+    let blk_data = [9; 32];
+    BeaconInstructionRequest {
+        signatures: committee.sign(&blk_data, &inst_root, indexes),
+        inst,
+        height,
+        inst_paths: Vec::new(),
+        inst_path_is_lefts: Vec::new(),
+        inst_root,
+        blk_data,
+        indexes: indexes.to_vec(),
+    }
+}
+
+fn process_set_threshold(program_id: &Pubkey, data: &mut [u8], threshold_info: BeaconInstructionRequest) -> ProgramResult {
+    let key = Pubkey::new_unique();
+    let mut lamports = 0;
+    let incognito_proxy = AccountInfo::new(&key, false, true, &mut lamports, data, program_id, false, 0);
+    process_instruction(
+        program_id,
+        &[incognito_proxy],
+        &BridgeInstruction::SetQuorumThreshold { threshold_info }.pack(),
+    )
+}
+
+#[test]
+fn test_set_quorum_threshold_success() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(5, 1);
+    let mut data = proxy_data(IncognitoProxy { beacons: committee.beacons(), ..proxy_info() });
+
+    let inst = threshold_inst(159, 1, 2);
+    assert_eq!(
+        process_set_threshold(&program_id, &mut data, threshold_request(&committee, &[0, 1, 3, 4], inst, 100)),
+        Ok(())
+    );
+    let incognito_proxy_info = IncognitoProxy::unpack(&data).unwrap();
+    assert_eq!(incognito_proxy_info.quorum_threshold, QuorumThreshold { numerator: 1, denominator: 2 });
+    assert_eq!(incognito_proxy_info.threshold_height, 100);
+
+    // three signatures are now enough
+    let inst = threshold_inst(159, 2, 3);
+    assert_eq!(
+        process_set_threshold(&program_id, &mut data, threshold_request(&committee, &[1, 2, 3], inst, 101)),
+        Ok(())
+    );
+    assert_eq!(IncognitoProxy::unpack(&data).unwrap().quorum_threshold, QuorumThreshold::default());
+}
+
+#[test]
+fn test_set_quorum_threshold_replay() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    let mut data = proxy_data(IncognitoProxy { threshold_height: 100, beacons: committee.beacons(), ..proxy_info() });
+
+    let inst = threshold_inst(159, 1, 2);
+    assert_eq!(
+        process_set_threshold(&program_id, &mut data, threshold_request(&committee, &[0, 1, 3], inst, 100)),
+        Err(BridgeError::StaleQuorumThreshold.into())
+    );
+}
+
+#[test]
+fn test_set_quorum_threshold_not_enough_signatures() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    let mut data = proxy_data(IncognitoProxy { beacons: committee.beacons(), ..proxy_info() });
+
+    let inst = threshold_inst(159, 1, 2);
+    assert_eq!(
+        process_set_threshold(&program_id, &mut data, threshold_request(&committee, &[0, 1], inst, 100)),
+        Err(BridgeError::InvalidNumberOfSignature.into())
+    );
+}
+
+#[test]
+fn test_set_quorum_threshold_invalid() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    let mut data = proxy_data(IncognitoProxy { beacons: committee.beacons(), ..proxy_info() });
+
+    for (numerator, denominator) in [(1, 0), (0, 1), (1, 3), (3, 3), (4, 3), (1, 256)] {
+        let inst = threshold_inst(159, numerator, denominator);
+        assert_eq!(
+            process_set_threshold(&program_id, &mut data, threshold_request(&committee, &[0, 1, 3], inst, 100)),
+            Err(BridgeError::InvalidQuorumThreshold.into())
+        );
+    }
+    let inst = threshold_inst(70, 1, 2);
+    assert_eq!(
+        process_set_threshold(&program_id, &mut data, threshold_request(&committee, &[0, 1, 3], inst, 100)),
+        Err(BridgeError::InvalidMetaType.into())
+    );
+}
+
+#[test]
+fn test_init_beacon_invalid_quorum_threshold() {
+    let program_id = Pubkey::new_unique();
+    let rent = Rent::default();
+    let mut rent_data = rent.lamports_per_byte_year.to_le_bytes().to_vec();
+    rent_data.extend_from_slice(&rent.exemption_threshold.to_le_bytes());
+    rent_data.push(rent.burn_percent);
+    let keys = [sysvar::rent::id(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    let mut lamports = [0, u32::MAX as u64, u32::MAX as u64, 0];
+    let mut data = [rent_data, vec![0; IncognitoProxy::space(DEFAULT_BEACON_CAPACITY)], vec![0; 64], vec![]];
+    let [rent_lamports, proxy_lamports, vault_lamports, guardian_lamports] = &mut lamports;
+    let [rent_data, proxy_data, vault_data, guardian_data] = &mut data;
+    let system_program = Pubkey::default();
+    let accounts = [
+        AccountInfo::new(&keys[0], false, false, rent_lamports, rent_data, &system_program, false, 0),
+        AccountInfo::new(&keys[1], false, true, proxy_lamports, proxy_data, &program_id, false, 0),
+        AccountInfo::new(&keys[2], false, true, vault_lamports, vault_data, &program_id, false, 0),
+        AccountInfo::new(&keys[3], true, false, guardian_lamports, guardian_data, &system_program, false, 0),
+    ];
+
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &accounts,
+            &BridgeInstruction::InitBeacon {
+                init_beacon_info: InitBeaconRequest {
+                    bump_seed: 255,
+                    vault: keys[2],
+                    quorum_threshold: QuorumThreshold { numerator: 3, denominator: 3 },
+                    ..InitBeaconRequest::default()
+                },
+            }
+            .pack(),
+        ),
+        Err(BridgeError::InvalidQuorumThreshold.into())
+    );
+}
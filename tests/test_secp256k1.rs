@@ -0,0 +1,232 @@
+mod helpers;
+
+use solana_program::{
+    account_info::AccountInfo,
+    instruction::Instruction,
+    keccak::hash,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    secp256k1_program,
+    sysvar::{
+        self,
+        instructions::{construct_instructions_data, store_current_index, BorrowedAccountMeta, BorrowedInstruction},
+    },
+};
+use solana_bridge::{
+    error::BridgeError,
+    instruction::{secp256k1_beacon_signatures, unshield_secp256k1, BridgeInstruction},
+    quorum::verify_secp256k1_signatures,
+    state::{QuorumThreshold, Secp256k1UnshieldRequest, UnshieldRequest},
+};
+
+use crate::helpers::beacon::Committee;
+
+const BLK_DATA: [u8; 32] = [1; 32];
+const INST_ROOT: [u8; 32] = [2; 32];
+
+// instructions sysvar data of `instructions` while executing the instruction at `current_index`
+fn instructions_data(instructions: &[Instruction], current_index: u16) -> Vec<u8> {
+    let borrowed: Vec<BorrowedInstruction> = instructions
+        .iter()
+        .map(|instruction| BorrowedInstruction {
+            program_id: &instruction.program_id,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| BorrowedAccountMeta {
+                    pubkey: &meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: &instruction.data,
+        })
+        .collect();
+    let mut data = construct_instructions_data(&borrowed);
+    store_current_index(&mut data, current_index);
+    data
+}
+
+fn verify(committee: &Committee, indexes: &[u8], instructions: &[Instruction], current_index: u16) -> Result<(), ProgramError> {
+    let key = sysvar::instructions::id();
+    let owner = sysvar::id();
+    let mut lamports = 0;
+    let mut data = instructions_data(instructions, current_index);
+    let instructions_sysvar = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+    verify_secp256k1_signatures(
+        &committee.beacons(),
+        QuorumThreshold::default(),
+        &BLK_DATA,
+        &INST_ROOT,
+        indexes,
+        &instructions_sysvar,
+    )
+}
+
+fn secp256k1_instruction(committee: &Committee, instruction_index: u8, indexes: &[u8]) -> Instruction {
+    let signatures = committee.sign(&BLK_DATA, &INST_ROOT, indexes);
+    secp256k1_beacon_signatures(instruction_index, &committee.beacons(), &BLK_DATA, &INST_ROOT, indexes, &signatures)
+}
+
+fn other_instruction() -> Instruction {
+    Instruction { program_id: Pubkey::new_unique(), accounts: vec![], data: vec![1, 2, 3] }
+}
+
+// checks the signatures the way the Secp256k1 program does, returning the recovered eth addresses
+fn precompile_verify(data: &[u8]) -> Vec<[u8; 20]> {
+    let offset = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    (0..data[0] as usize)
+        .map(|i| {
+            let start = 1 + i * 11;
+            let (signature_offset, eth_address_offset) = (offset(start), offset(start + 3));
+            let (message_offset, message_size) = (offset(start + 6), offset(start + 8));
+            let signature = libsecp256k1::Signature::parse_standard_slice(&data[signature_offset..signature_offset + 64]).unwrap();
+            let recovery_id = libsecp256k1::RecoveryId::parse(data[signature_offset + 64]).unwrap();
+            let message_hash = hash(&data[message_offset..message_offset + message_size]).to_bytes();
+            let pubkey = libsecp256k1::recover(&libsecp256k1::Message::parse(&message_hash), &signature, &recovery_id).unwrap();
+            let mut eth_address = [0; 20];
+            eth_address.copy_from_slice(&hash(&pubkey.serialize()[1..]).to_bytes()[12..]);
+            assert_eq!(&data[eth_address_offset..eth_address_offset + 20], &eth_address[..]);
+            eth_address
+        })
+        .collect()
+}
+
+#[test]
+fn test_secp256k1_instruction_layout() {
+    let committee = Committee::new(4, 1);
+    let instruction = secp256k1_instruction(&committee, 2, &[0, 2, 3]);
+    assert_eq!(instruction.program_id, secp256k1_program::id());
+    assert!(instruction.accounts.is_empty());
+    assert_eq!(instruction.data[0], 3);
+    for i in 0..3 {
+        let offsets = &instruction.data[1 + i * 11..1 + (i + 1) * 11];
+        assert_eq!([offsets[2], offsets[5], offsets[10]], [2, 2, 2]);
+    }
+    assert_eq!(precompile_verify(&instruction.data).len(), 3);
+}
+
+#[test]
+fn test_verify_secp256k1_signatures() {
+    let committee = Committee::new(4, 1);
+    let instructions = [other_instruction(), secp256k1_instruction(&committee, 1, &[0, 2, 3]), other_instruction()];
+    assert_eq!(verify(&committee, &[0, 2, 3], &instructions, 2), Ok(()));
+    // the secp256k1 instruction must be right before the current one
+    assert_eq!(
+        verify(&committee, &[0, 2, 3], &instructions, 1),
+        Err(BridgeError::MissingSecp256k1Instruction.into())
+    );
+    assert_eq!(
+        verify(&committee, &[0, 2, 3], &instructions, 0),
+        Err(BridgeError::MissingSecp256k1Instruction.into())
+    );
+}
+
+#[test]
+fn test_verify_secp256k1_not_enough_signatures() {
+    let committee = Committee::new(4, 1);
+    let instructions = [secp256k1_instruction(&committee, 0, &[0, 2]), other_instruction()];
+    assert_eq!(
+        verify(&committee, &[0, 2], &instructions, 1),
+        Err(BridgeError::InvalidNumberOfSignature.into())
+    );
+    // fewer signatures checked than claimed indexes
+    assert_eq!(
+        verify(&committee, &[0, 2, 3], &instructions, 1),
+        Err(BridgeError::InvalidSecp256k1Instruction.into())
+    );
+}
+
+#[test]
+fn test_verify_secp256k1_mismatched_beacons() {
+    let committee = Committee::new(4, 1);
+    let instructions = [secp256k1_instruction(&committee, 0, &[0, 2, 3]), other_instruction()];
+    assert_eq!(
+        verify(&committee, &[0, 1, 3], &instructions, 1),
+        Err(BridgeError::InvalidBeaconSignature.into())
+    );
+    // signatures of another committee
+    let instructions = [secp256k1_instruction(&Committee::new(4, 2), 0, &[0, 2, 3]), other_instruction()];
+    assert_eq!(
+        verify(&committee, &[0, 2, 3], &instructions, 1),
+        Err(BridgeError::InvalidBeaconSignature.into())
+    );
+}
+
+#[test]
+fn test_verify_secp256k1_other_block() {
+    let committee = Committee::new(4, 1);
+    let indexes = [0, 2, 3];
+    let signatures = committee.sign(&BLK_DATA, &[3; 32], &indexes);
+    let instruction = secp256k1_beacon_signatures(0, &committee.beacons(), &BLK_DATA, &[3; 32], &indexes, &signatures);
+    assert_eq!(
+        verify(&committee, &indexes, &[instruction, other_instruction()], 1),
+        Err(BridgeError::InvalidSecp256k1Instruction.into())
+    );
+}
+
+#[test]
+fn test_verify_secp256k1_offsets_into_other_instruction() {
+    let committee = Committee::new(4, 1);
+    // offsets built for a secp256k1 instruction at index 1
+    let instructions = [secp256k1_instruction(&committee, 1, &[0, 2, 3]), other_instruction()];
+    assert_eq!(
+        verify(&committee, &[0, 2, 3], &instructions, 1),
+        Err(BridgeError::InvalidSecp256k1Instruction.into())
+    );
+}
+
+#[test]
+fn test_unshield_secp256k1_instructions() {
+    let committee = Committee::new(4, 1);
+    let program_id = Pubkey::new_unique();
+    let incognito_proxy = Pubkey::new_unique();
+    let (_, bump_seed) = Pubkey::find_program_address(&[incognito_proxy.as_ref()], &program_id);
+    let indexes = vec![1, 2, 3];
+    let unshield_info = UnshieldRequest {
+        inst: [0; 162],
+        height: 7,
+        inst_paths: vec![[4; 32]],
+        inst_path_is_lefts: vec![true],
+        inst_root: INST_ROOT,
+        blk_data: BLK_DATA,
+        signatures: committee.sign(&BLK_DATA, &INST_ROOT, &indexes),
+        indexes: indexes.clone(),
+    };
+
+    let instructions = unshield_secp256k1(
+        program_id,
+        unshield_info,
+        &committee.beacons(),
+        3,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        incognito_proxy,
+        bump_seed,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    assert_eq!(instructions.len(), 2);
+    assert_eq!(precompile_verify(&instructions[0].data).len(), 3);
+    assert_eq!(instructions[1].accounts.len(), 15);
+    assert_eq!(instructions[1].accounts[14].pubkey, sysvar::instructions::id());
+    assert_eq!(
+        BridgeInstruction::unpack(&instructions[1].data).unwrap(),
+        BridgeInstruction::UnshieldSecp256k1 {
+            unshield_info: Secp256k1UnshieldRequest {
+                inst: [0; 162],
+                height: 7,
+                inst_paths: vec![[4; 32]],
+                inst_path_is_lefts: vec![true],
+                inst_root: INST_ROOT,
+                blk_data: BLK_DATA,
+                indexes: indexes.clone(),
+            },
+        }
+    );
+
+    let mut transaction = vec![other_instruction(), other_instruction(), other_instruction()];
+    transaction.extend(instructions);
+    assert_eq!(verify(&committee, &indexes, &transaction, 4), Ok(()));
+}
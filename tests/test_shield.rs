@@ -13,13 +13,15 @@ use solana_sdk::{
 use solana_bridge::{
     instruction::BridgeInstruction,
     processor::process_instruction,
-    state::{IncognitoProxy, MAX_BEACON_ADDRESSES},
+    state::{IncognitoProxy, QuorumThreshold, DEFAULT_BEACON_CAPACITY},
 };
 use spl_token::{
     instruction::approve,
     state::{Account as Token, AccountState, Mint},
 };
-use solana_bridge::instruction::shield;
+use solana_bridge::instruction::{shield, shield_native};
+use solana_program::{program_option::COption, program_pack::Pack, rent::Rent, system_program};
+use spl_associated_token_account::get_associated_token_address;
 
 use crate::helpers::{add_packable_account, get_token_balance};
 
@@ -62,21 +64,34 @@ async fn test_shield_success() {
         processor!(process_instruction),
     );
 
-    // limit to track compute unit increase
-    test.set_compute_max_units(38_000);
+    // limit to track compute unit increase, with room for the proxy validation and shield event
+    test.set_compute_max_units(60_000);
 
     println!("bump seeed in test {}", bump_seed);
-    add_packable_account(
-        &mut test,
-        incognito_proxy,
-        u32::MAX as u64,
-        &IncognitoProxy::new(IncognitoProxy {
+    let mut incognito_proxy_data = vec![0; IncognitoProxy::space(DEFAULT_BEACON_CAPACITY)];
+    IncognitoProxy::pack(
+        IncognitoProxy {
             is_initialized: true,
             bump_seed,
             vault: vault_account_id,
+            start_height: 0,
+            guardian: Pubkey::default(),
+            is_paused: false,
+            quorum_threshold: QuorumThreshold::default(),
+            threshold_height: 0,
             beacons: Vec::new(), // todo add beacons
-        }),
-        &program_id,
+        },
+        &mut incognito_proxy_data,
+    )
+    .unwrap();
+    test.add_account(
+        incognito_proxy,
+        Account {
+            lamports: u32::MAX as u64,
+            data: incognito_proxy_data,
+            owner: program_id,
+            ..Account::default()
+        },
     );
 
     // init shield maker token account
@@ -151,4 +166,102 @@ async fn test_shield_success() {
         after_vault_token_account,
         initial_vault_token_account + deposit_amount
     );
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_shield_native_success() {
+    // new shield maker account
+    let shield_maker = Keypair::new();
+    // new vault program id
+    let program_id = Pubkey::new_unique();
+    // incognito proxy account
+    let incognito_proxy = Pubkey::new_unique();
+    let (vault_authority, bump_seed) =
+        Pubkey::find_program_address(&[incognito_proxy.as_ref()], &program_id);
+    // vault native mint token account
+    let vault_token_account = get_associated_token_address(&vault_authority, &spl_token::native_mint::id());
+
+    let deposit_amount: u64 = 100_0000;
+    let rent_exempt_reserve = Rent::default().minimum_balance(Token::LEN);
+
+    let mut test = ProgramTest::new(
+        "bridge_solana",
+        program_id,
+        processor!(process_instruction),
+    );
+
+    let mut incognito_proxy_data = vec![0; IncognitoProxy::space(DEFAULT_BEACON_CAPACITY)];
+    IncognitoProxy::pack(
+        IncognitoProxy {
+            is_initialized: true,
+            bump_seed,
+            vault: Pubkey::new_unique(),
+            start_height: 0,
+            guardian: Pubkey::default(),
+            is_paused: false,
+            quorum_threshold: QuorumThreshold::default(),
+            threshold_height: 0,
+            beacons: Vec::new(),
+        },
+        &mut incognito_proxy_data,
+    )
+    .unwrap();
+    test.add_account(
+        incognito_proxy,
+        Account {
+            lamports: u32::MAX as u64,
+            data: incognito_proxy_data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    add_packable_account(
+        &mut test,
+        vault_token_account,
+        rent_exempt_reserve,
+        &Token {
+            mint: spl_token::native_mint::id(),
+            owner: vault_authority,
+            amount: 0,
+            is_native: COption::Some(rent_exempt_reserve),
+            state: AccountState::Initialized,
+            ..Token::default()
+        },
+        &spl_token::id(),
+    );
+
+    test.add_account(
+        shield_maker.pubkey(),
+        Account::new(deposit_amount * 2, 0, &system_program::id()),
+    );
+
+    let (mut banks_client, payer, recent_blockhash) = test.start().await;
+
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            shield_native(
+                program_id,
+                deposit_amount,
+                shield_maker.pubkey(),
+                incognito_proxy,
+                bump_seed,
+                &[1; 148],
+            ),
+        ],
+        Some(&payer.pubkey()),
+    );
+
+    transaction.sign(
+        &vec![&payer, &shield_maker],
+        recent_blockhash,
+    );
+    assert!(banks_client.process_transaction(transaction).await.is_ok());
+
+    assert_eq!(
+        get_token_balance(&mut banks_client, vault_token_account).await,
+        deposit_amount
+    );
+    let shield_maker_account = banks_client.get_account(shield_maker.pubkey()).await.unwrap().unwrap();
+    assert_eq!(shield_maker_account.lamports, deposit_amount);
+}
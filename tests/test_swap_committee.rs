@@ -0,0 +1,138 @@
+mod helpers;
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+    secp256k1_recover::Secp256k1Pubkey,
+};
+use solana_bridge::{
+    error::BridgeError,
+    instruction::BridgeInstruction,
+    processor::process_instruction,
+    state::{IncognitoProxy, BeaconInstructionRequest, DEFAULT_BEACON_CAPACITY},
+};
+
+use crate::helpers::{
+    beacon::{inst_leaf, u256, Committee},
+    fixtures::{proxy_data, proxy_info},
+};
+
+const BEACON_HEIGHT: u64 = 100;
+
+fn swap_inst(meta_type: u8, start_height: u64, beacons: &[Secp256k1Pubkey]) -> Vec<u8> {
+    let mut inst = vec![meta_type, 1];
+    inst.extend_from_slice(&u256(start_height));
+    inst.extend_from_slice(&u256(beacons.len() as u64));
+    for beacon in beacons {
+        inst.extend_from_slice(&beacon.to_bytes());
+    }
+    inst
+}
+
+fn swap_request(committee: &Committee, indexes: &[u8], inst: Vec<u8>) -> BeaconInstructionRequest {
+    let inst_root = inst_leaf(&inst, BEACON_HEIGHT);
+    let blk_data = [9; 32];
+    BeaconInstructionRequest {
+        signatures: committee.sign(&blk_data, &inst_root, indexes),
+        inst,
+        height: BEACON_HEIGHT,
+        inst_paths: Vec::new(),
+        inst_path_is_lefts: Vec::new(),
+        inst_root,
+        blk_data,
+        indexes: indexes.to_vec(),
+    }
+}
+
+fn process_swap(program_id: &Pubkey, data: &mut [u8], swap_info: BeaconInstructionRequest) -> ProgramResult {
+    let key = Pubkey::new_unique();
+    let mut lamports = 0;
+    let incognito_proxy = AccountInfo::new(&key, false, true, &mut lamports, data, program_id, false, 0);
+    process_instruction(
+        program_id,
+        &[incognito_proxy],
+        &BridgeInstruction::SwapBeaconCommittee { swap_info }.pack(),
+    )
+}
+
+#[test]
+fn test_swap_committee_success() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    let new_committee = Committee::new(5, 2);
+    let mut data = proxy_data(IncognitoProxy { beacons: committee.beacons(), ..proxy_info() });
+
+    let inst = swap_inst(70, 50, &new_committee.beacons());
+    assert_eq!(process_swap(&program_id, &mut data, swap_request(&committee, &[0, 1, 3], inst)), Ok(()));
+
+    let incognito_proxy_info = IncognitoProxy::unpack(&data).unwrap();
+    assert_eq!(incognito_proxy_info.start_height, 50);
+    assert!(incognito_proxy_info.beacons == new_committee.beacons());
+}
+
+#[test]
+fn test_swap_committee_stale_start_height() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    let mut data = proxy_data(IncognitoProxy { start_height: 50, beacons: committee.beacons(), ..proxy_info() });
+
+    let inst = swap_inst(70, 50, &Committee::new(4, 2).beacons());
+    assert_eq!(
+        process_swap(&program_id, &mut data, swap_request(&committee, &[0, 1, 2], inst)),
+        Err(BridgeError::InvalidCommitteeStartHeight.into())
+    );
+}
+
+#[test]
+fn test_swap_committee_not_enough_signatures() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    let mut data = proxy_data(IncognitoProxy { beacons: committee.beacons(), ..proxy_info() });
+
+    let inst = swap_inst(70, 50, &Committee::new(4, 2).beacons());
+    assert_eq!(
+        process_swap(&program_id, &mut data, swap_request(&committee, &[0, 1], inst)),
+        Err(BridgeError::InvalidNumberOfSignature.into())
+    );
+}
+
+#[test]
+fn test_swap_committee_signed_by_other_committee() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    let new_committee = Committee::new(4, 2);
+    let mut data = proxy_data(IncognitoProxy { beacons: committee.beacons(), ..proxy_info() });
+
+    let inst = swap_inst(70, 50, &new_committee.beacons());
+    assert_eq!(
+        process_swap(&program_id, &mut data, swap_request(&new_committee, &[0, 1, 2], inst)),
+        Err(BridgeError::InvalidBeaconSignature.into())
+    );
+}
+
+#[test]
+fn test_swap_committee_invalid_meta_type() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    let mut data = proxy_data(IncognitoProxy { beacons: committee.beacons(), ..proxy_info() });
+
+    let inst = swap_inst(157, 50, &Committee::new(4, 2).beacons());
+    assert_eq!(
+        process_swap(&program_id, &mut data, swap_request(&committee, &[0, 1, 2], inst)),
+        Err(BridgeError::InvalidMetaType.into())
+    );
+}
+
+#[test]
+fn test_swap_committee_exceeds_capacity() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    let mut data = proxy_data(IncognitoProxy { beacons: committee.beacons(), ..proxy_info() });
+
+    let inst = swap_inst(70, 50, &Committee::new(DEFAULT_BEACON_CAPACITY + 1, 2).beacons());
+    assert_eq!(
+        process_swap(&program_id, &mut data, swap_request(&committee, &[0, 1, 2], inst)),
+        Err(BridgeError::BeaconCapacityExceeded.into())
+    );
+}
@@ -0,0 +1,63 @@
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+use solana_bridge::{
+    error::BridgeError,
+    instruction::BridgeInstruction,
+    processor::process_instruction,
+    state::{DappRequest, UnshieldRequest},
+};
+
+// unshield accounts followed by dapp accounts without forwarded accounts, the proof is never checked
+fn process_unshield_and_call(meta_type: u8, maker_is_signer: bool, dapp_signer: Option<Pubkey>) -> ProgramResult {
+    let program_id = Pubkey::new_unique();
+    let mut keys: Vec<Pubkey> = (0..18).map(|_| Pubkey::new_unique()).collect();
+    keys[14] = dapp_signer.unwrap_or(keys[1]);
+    keys[16] = keys[4];
+    let mut lamports = [0; 18];
+    let mut data = vec![vec![]; 18];
+    let accounts: Vec<AccountInfo> = keys
+        .iter()
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .enumerate()
+        .map(|(i, ((key, lamports), data))| {
+            let is_signer = (i == 1 && maker_is_signer) || i == 8 || i == 14;
+            AccountInfo::new(key, is_signer, false, lamports, data, &program_id, false, 0)
+        })
+        .collect();
+    let mut inst = [0; 162];
+    inst[0] = meta_type;
+    inst[1] = 1;
+
+    process_instruction(
+        &program_id,
+        &accounts,
+        &BridgeInstruction::UnshieldAndCall {
+            unshield_info: UnshieldRequest {
+                inst,
+                height: 1,
+                inst_paths: Vec::new(),
+                inst_path_is_lefts: Vec::new(),
+                inst_root: [0; 32],
+                blk_data: [0; 32],
+                indexes: Vec::new(),
+                signatures: Vec::new(),
+            },
+            dapp_request: DappRequest { inst: vec![1], num_acc: 0, sign_index: 0, ..DappRequest::default() },
+        }
+        .pack(),
+    )
+}
+
+#[test]
+fn test_unshield_and_call_requires_unshield_maker_signature() {
+    assert_eq!(process_unshield_and_call(158, false, None), Err(BridgeError::InvalidSigner.into()));
+    assert_eq!(
+        process_unshield_and_call(158, true, Some(Pubkey::new_unique())),
+        Err(BridgeError::InvalidSigner.into())
+    );
+}
+
+#[test]
+fn test_unshield_and_call_requires_pda_meta_type() {
+    assert_eq!(process_unshield_and_call(157, true, None), Err(BridgeError::InvalidMetaType.into()));
+}
@@ -0,0 +1,297 @@
+mod helpers;
+
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+    sysvar,
+};
+use solana_bridge::{
+    error::BridgeError,
+    instruction::BridgeInstruction,
+    processor::process_instruction,
+    state::{IncognitoProxy, UnshieldRequest},
+    validation::{
+        assert_incognito_proxy, assert_owned_by, assert_pda, assert_signer, assert_signer_authority,
+        assert_system_program, assert_token_account, assert_token_program, assert_vault_authority,
+        assert_vault_token_account, assert_writable,
+    },
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::helpers::fixtures::{proxy_data, proxy_info, token_account_data};
+
+#[test]
+fn test_assert_signer_and_writable() {
+    let key = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (mut lamports, mut data) = (0, vec![]);
+    let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+    assert_eq!(assert_signer(&account), Err(BridgeError::InvalidSigner.into()));
+    assert_eq!(assert_writable(&account), Err(BridgeError::AccountNotWritable.into()));
+
+    let (mut lamports, mut data) = (0, vec![]);
+    let account = AccountInfo::new(&key, true, true, &mut lamports, &mut data, &owner, false, 0);
+    assert_eq!(assert_signer(&account), Ok(()));
+    assert_eq!(assert_writable(&account), Ok(()));
+}
+
+#[test]
+fn test_assert_owned_by() {
+    let key = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (mut lamports, mut data) = (0, vec![]);
+    let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+    assert_eq!(assert_owned_by(&account, &owner), Ok(()));
+    assert_eq!(
+        assert_owned_by(&account, &Pubkey::new_unique()),
+        Err(BridgeError::InvalidAccountOwner.into())
+    );
+}
+
+#[test]
+fn test_assert_program_ids() {
+    let key = Pubkey::new_unique();
+    let loader = Pubkey::new_unique();
+    let (mut lamports, mut data) = (0, vec![]);
+    let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &loader, true, 0);
+    assert_eq!(assert_token_program(&account), Err(BridgeError::InvalidTokenProgram.into()));
+    assert_eq!(assert_system_program(&account), Err(BridgeError::InvalidSystemProgram.into()));
+
+    let token_program = spl_token::id();
+    let (mut lamports, mut data) = (0, vec![]);
+    let account = AccountInfo::new(&token_program, false, false, &mut lamports, &mut data, &loader, true, 0);
+    assert_eq!(assert_token_program(&account), Ok(()));
+
+    let system_program = system_program::id();
+    let (mut lamports, mut data) = (0, vec![]);
+    let account = AccountInfo::new(&system_program, false, false, &mut lamports, &mut data, &loader, true, 0);
+    assert_eq!(assert_system_program(&account), Ok(()));
+}
+
+#[test]
+fn test_assert_pda() {
+    let program_id = Pubkey::new_unique();
+    let signer = Pubkey::new_unique();
+    let (pda, bump) = Pubkey::find_program_address(&[signer.as_ref()], &program_id);
+    let (mut lamports, mut data) = (0, vec![]);
+    let account = AccountInfo::new(&pda, false, false, &mut lamports, &mut data, &program_id, false, 0);
+    assert_eq!(assert_signer_authority(&account, &signer, &program_id), Ok(bump));
+    assert_eq!(
+        assert_signer_authority(&account, &Pubkey::new_unique(), &program_id),
+        Err(BridgeError::InvalidSignerTokenAuth.into())
+    );
+    assert_eq!(
+        assert_pda(&account, &[b"other"], &program_id, BridgeError::InvalidAllowlistAccount),
+        Err(BridgeError::InvalidAllowlistAccount.into())
+    );
+}
+
+#[test]
+fn test_assert_incognito_proxy() {
+    let program_id = Pubkey::new_unique();
+    let key = Pubkey::new_unique();
+    let (mut lamports, mut data) = (0, proxy_data(proxy_info()));
+    let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &program_id, false, 0);
+    assert_eq!(assert_incognito_proxy(&account, &program_id).unwrap().bump_seed, 255);
+    assert_eq!(
+        assert_incognito_proxy(&account, &Pubkey::new_unique()).unwrap_err(),
+        BridgeError::InvalidAccountOwner.into()
+    );
+
+    let (mut lamports, mut data) = (0, proxy_data(IncognitoProxy { is_initialized: false, ..proxy_info() }));
+    let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &program_id, false, 0);
+    assert_eq!(
+        assert_incognito_proxy(&account, &program_id).unwrap_err(),
+        BridgeError::BeaconsUnInitialized.into()
+    );
+}
+
+#[test]
+fn test_assert_vault_authority() {
+    let program_id = Pubkey::new_unique();
+    let proxy = Pubkey::new_unique();
+    let (vault_authority, bump) = Pubkey::find_program_address(&[proxy.as_ref()], &program_id);
+    let (mut lamports, mut data) = (0, vec![]);
+    let account = AccountInfo::new(&vault_authority, false, false, &mut lamports, &mut data, &program_id, false, 0);
+    assert_eq!(assert_vault_authority(&account, &proxy, bump, &program_id), Ok(()));
+    assert_eq!(
+        assert_vault_authority(&account, &Pubkey::new_unique(), bump, &program_id),
+        Err(BridgeError::InvalidVaultAuthority.into())
+    );
+
+    let key = Pubkey::new_unique();
+    let (mut lamports, mut data) = (0, vec![]);
+    let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &program_id, false, 0);
+    assert_eq!(
+        assert_vault_authority(&account, &proxy, bump, &program_id),
+        Err(BridgeError::InvalidVaultAuthority.into())
+    );
+}
+
+#[test]
+fn test_assert_vault_token_account() {
+    let program_id = Pubkey::new_unique();
+    let proxy = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let (vault_authority, bump) = Pubkey::find_program_address(&[proxy.as_ref()], &program_id);
+    let vault_token_account = get_associated_token_address(&vault_authority, &mint);
+    let token_program = spl_token::id();
+
+    let (mut lamports, mut data) = (0, token_account_data(mint, vault_authority, 1));
+    let account = AccountInfo::new(&vault_token_account, false, true, &mut lamports, &mut data, &token_program, false, 0);
+    assert_eq!(assert_vault_token_account(&account, &proxy, bump, &program_id), Ok(mint));
+    assert_eq!(assert_token_account(&account).unwrap().amount, 1);
+
+    let other_account = Pubkey::new_unique();
+    let (mut lamports, mut data) = (0, token_account_data(mint, vault_authority, 1));
+    let account = AccountInfo::new(&other_account, false, true, &mut lamports, &mut data, &token_program, false, 0);
+    assert_eq!(
+        assert_vault_token_account(&account, &proxy, bump, &program_id),
+        Err(BridgeError::InvalidVaultTokenAccount.into())
+    );
+
+    let (mut lamports, mut data) = (0, token_account_data(mint, vault_authority, 1));
+    let account = AccountInfo::new(&vault_token_account, false, true, &mut lamports, &mut data, &program_id, false, 0);
+    assert_eq!(
+        assert_vault_token_account(&account, &proxy, bump, &program_id),
+        Err(BridgeError::InvalidAccountOwner.into())
+    );
+}
+
+// unshield accounts for meta type 157 with `update` applied, the proof is never checked
+fn process_unshield(update: impl FnOnce(&mut [Pubkey; 14])) -> Result<(), ProgramError> {
+    process_unshield_inst(update, |_| {})
+}
+
+fn process_unshield_inst(
+    update: impl FnOnce(&mut [Pubkey; 14]),
+    update_inst: impl FnOnce(&mut [u8; 162]),
+) -> Result<(), ProgramError> {
+    let program_id = Pubkey::new_unique();
+    let proxy = Pubkey::new_unique();
+    let unshield_maker = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let (vault_authority, bump) = Pubkey::find_program_address(&[proxy.as_ref()], &program_id);
+    let mut keys = [
+        get_associated_token_address(&vault_authority, &mint),
+        unshield_maker,
+        vault_authority,
+        Pubkey::new_unique(),
+        proxy,
+        spl_token::id(),
+        get_associated_token_address(&unshield_maker, &mint),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        system_program::id(),
+        unshield_maker,
+        mint,
+        spl_associated_token_account::id(),
+        sysvar::rent::id(),
+    ];
+    let vault = keys[3];
+    update(&mut keys);
+    let token_program = spl_token::id();
+    let system_owner = Pubkey::default();
+    let mut lamports = [0; 14];
+    let mut data = vec![vec![]; 14];
+    data[0] = token_account_data(mint, vault_authority, 1);
+    data[4] = proxy_data(IncognitoProxy { bump_seed: bump, vault, ..proxy_info() });
+    let accounts: Vec<AccountInfo> = keys
+        .iter()
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .enumerate()
+        .map(|(i, ((key, lamports), data))| {
+            let owner = match i {
+                0 => &token_program,
+                3 | 4 => &program_id,
+                _ => &system_owner,
+            };
+            let is_writable = matches!(i, 0 | 6 | 7 | 8);
+            AccountInfo::new(key, i == 8, is_writable, lamports, data, owner, matches!(i, 5 | 9 | 12), 0)
+        })
+        .collect();
+    let mut inst = [0; 162];
+    inst[0] = 157;
+    inst[1] = 1;
+    inst[2..34].copy_from_slice(mint.as_ref());
+    inst[34..66].copy_from_slice(unshield_maker.as_ref());
+    update_inst(&mut inst);
+
+    process_instruction(
+        &program_id,
+        &accounts,
+        &BridgeInstruction::UnShield {
+            unshield_info: UnshieldRequest {
+                inst,
+                height: 1,
+                inst_paths: Vec::new(),
+                inst_path_is_lefts: Vec::new(),
+                inst_root: [0; 32],
+                blk_data: [0; 32],
+                indexes: Vec::new(),
+                signatures: Vec::new(),
+            },
+        }
+        .pack(),
+    )
+}
+
+#[test]
+fn test_unshield_reaches_proof_with_valid_accounts() {
+    assert_eq!(process_unshield(|_| {}), Err(BridgeError::InvalidNumberOfSignature.into()));
+}
+
+#[test]
+fn test_unshield_amount_overflows_u64() {
+    assert_eq!(
+        process_unshield_inst(|_| {}, |inst| inst[66] = 1),
+        Err(BridgeError::InvalidBeaconInstruction.into())
+    );
+    assert_eq!(
+        process_unshield_inst(|_| {}, |inst| inst[90..98].copy_from_slice(&u64::MAX.to_be_bytes())),
+        Err(BridgeError::InvalidNumberOfSignature.into())
+    );
+}
+
+#[test]
+fn test_unshield_invalid_vault_authority() {
+    assert_eq!(
+        process_unshield(|keys| keys[2] = Pubkey::new_unique()),
+        Err(BridgeError::InvalidVaultAuthority.into())
+    );
+}
+
+#[test]
+fn test_unshield_invalid_programs() {
+    assert_eq!(
+        process_unshield(|keys| keys[5] = Pubkey::new_unique()),
+        Err(BridgeError::InvalidTokenProgram.into())
+    );
+    assert_eq!(
+        process_unshield(|keys| keys[9] = Pubkey::new_unique()),
+        Err(BridgeError::InvalidSystemProgram.into())
+    );
+    assert_eq!(
+        process_unshield(|keys| keys[12] = Pubkey::new_unique()),
+        Err(BridgeError::InvalidAssociatedTokenProgram.into())
+    );
+}
+
+#[test]
+fn test_unshield_invalid_receiver_accounts() {
+    assert_eq!(
+        process_unshield(|keys| keys[6] = Pubkey::new_unique()),
+        Err(BridgeError::InvalidKeysInInstruction.into())
+    );
+    assert_eq!(
+        process_unshield(|keys| keys[10] = Pubkey::new_unique()),
+        Err(BridgeError::InvalidKeysInInstruction.into())
+    );
+    assert_eq!(
+        process_unshield(|keys| keys[11] = Pubkey::new_unique()),
+        Err(BridgeError::InvalidKeysInInstruction.into())
+    );
+}
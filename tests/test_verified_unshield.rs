@@ -0,0 +1,273 @@
+mod helpers;
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+    sysvar,
+};
+use solana_bridge::{
+    error::BridgeError,
+    instruction::{find_burn_tx_address, find_verified_root_address, BridgeInstruction},
+    processor::process_instruction,
+    state::{BeaconBlockRequest, IncognitoProxy, VerifiedUnshieldRequest},
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::helpers::{
+    beacon::{inst_leaf, Committee},
+    fixtures::{proxy_data, proxy_info, token_account_data},
+};
+
+const BEACON_HEIGHT: u64 = 100;
+const MINT: [u8; 32] = [1; 32];
+const RECEIVER: [u8; 32] = [2; 32];
+
+fn process_verify(
+    program_id: &Pubkey,
+    proxy: Pubkey,
+    verified_root: Pubkey,
+    verified_root_owner: Pubkey,
+    block_info: BeaconBlockRequest,
+) -> ProgramResult {
+    let committee = Committee::new(4, 1);
+    let keys = [proxy, verified_root, Pubkey::new_unique(), system_program::id()];
+    let mut lamports = [0; 4];
+    let mut data = [proxy_data(IncognitoProxy { beacons: committee.beacons(), ..proxy_info() }), vec![], vec![], vec![]];
+    let [proxy_lamports, root_lamports, payer_lamports, system_lamports] = &mut lamports;
+    let [proxy_data, root_data, payer_data, system_data] = &mut data;
+    let system_owner = system_program::id();
+    let loader = Pubkey::new_unique();
+    let accounts = [
+        AccountInfo::new(&keys[0], false, false, proxy_lamports, proxy_data, program_id, false, 0),
+        AccountInfo::new(&keys[1], false, true, root_lamports, root_data, &verified_root_owner, false, 0),
+        AccountInfo::new(&keys[2], true, true, payer_lamports, payer_data, &system_owner, false, 0),
+        AccountInfo::new(&keys[3], false, false, system_lamports, system_data, &loader, true, 0),
+    ];
+    process_instruction(program_id, &accounts, &BridgeInstruction::VerifyBeaconBlock { block_info }.pack())
+}
+
+fn block_request(committee: &Committee, indexes: &[u8], inst_root: [u8; 32]) -> BeaconBlockRequest {
+    let blk_data = [9; 32];
+    BeaconBlockRequest {
+        inst_root,
+        blk_data,
+        indexes: indexes.to_vec(),
+        signatures: committee.sign(&blk_data, &inst_root, indexes),
+    }
+}
+
+#[test]
+fn test_verify_beacon_block() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    let proxy = Pubkey::new_unique();
+    let (verified_root, _) = find_verified_root_address(&program_id, &proxy, &[5; 32]);
+
+    // signatures are checked before the verified root is created
+    assert_eq!(
+        process_verify(&program_id, proxy, verified_root, system_program::id(), block_request(&committee, &[0, 1], [5; 32])),
+        Err(BridgeError::InvalidNumberOfSignature.into())
+    );
+    assert_eq!(
+        process_verify(
+            &program_id,
+            proxy,
+            verified_root,
+            system_program::id(),
+            block_request(&Committee::new(4, 2), &[0, 1, 2], [5; 32]),
+        ),
+        Err(BridgeError::InvalidBeaconSignature.into())
+    );
+    assert_eq!(
+        process_verify(&program_id, proxy, verified_root, system_program::id(), block_request(&committee, &[0, 1, 2], [5; 32])),
+        Err(ProgramError::UnsupportedSysvar)
+    );
+}
+
+#[test]
+fn test_verify_beacon_block_invalid_account() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    assert_eq!(
+        process_verify(
+            &program_id,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            system_program::id(),
+            block_request(&committee, &[0, 1, 2], [5; 32]),
+        ),
+        Err(BridgeError::InvalidVerifiedRootAccount.into())
+    );
+
+    let proxy = Pubkey::new_unique();
+    let (verified_root, _) = find_verified_root_address(&program_id, &proxy, &[5; 32]);
+    assert_eq!(
+        process_verify(&program_id, proxy, verified_root, program_id, block_request(&committee, &[0, 1, 2], [5; 32])),
+        Err(BridgeError::BeaconBlockAlreadyVerified.into())
+    );
+    // the account of another block cannot be used
+    assert_eq!(
+        process_verify(&program_id, proxy, verified_root, system_program::id(), block_request(&committee, &[0, 1, 2], [6; 32])),
+        Err(BridgeError::InvalidVerifiedRootAccount.into())
+    );
+}
+
+#[test]
+fn test_verify_beacon_block_not_blocked_by_other_block() {
+    let program_id = Pubkey::new_unique();
+    let committee = Committee::new(4, 1);
+    let proxy = Pubkey::new_unique();
+
+    // a block verified first, whatever height it claims, leaves the account of any other block free
+    let (verified_root, _) = find_verified_root_address(&program_id, &proxy, &[5; 32]);
+    assert_eq!(
+        process_verify(&program_id, proxy, verified_root, program_id, block_request(&committee, &[0, 1, 2], [5; 32])),
+        Err(BridgeError::BeaconBlockAlreadyVerified.into())
+    );
+    let (other_root, _) = find_verified_root_address(&program_id, &proxy, &[6; 32]);
+    assert_ne!(other_root, verified_root);
+    assert_eq!(
+        process_verify(&program_id, proxy, other_root, system_program::id(), block_request(&committee, &[0, 1, 2], [6; 32])),
+        Err(ProgramError::UnsupportedSysvar)
+    );
+}
+
+// unshield of meta type 157 to the receiver `RECEIVER` of `MINT`
+fn unshield_inst() -> [u8; 162] {
+    let mut inst = [0; 162];
+    inst[0] = 157;
+    inst[1] = 1;
+    inst[2..34].copy_from_slice(&MINT);
+    inst[34..66].copy_from_slice(&RECEIVER);
+    inst[98..130].copy_from_slice(&[3; 32]);
+    inst
+}
+
+// unshield accounts followed by the verified root of `stored_root`, the unshield is proven
+// at `height` against `inst_root` to a committee starting at `start_height`
+fn process_unshield_verified(
+    height: u64,
+    inst_root: [u8; 32],
+    stored_root: [u8; 32],
+    root_owner: Option<Pubkey>,
+    start_height: u64,
+) -> ProgramResult {
+    let program_id = Pubkey::new_unique();
+    let proxy = Pubkey::new_unique();
+    let unshield_maker = Pubkey::new_from_array(RECEIVER);
+    let mint = Pubkey::new_from_array(MINT);
+    let (vault_authority, bump) = Pubkey::find_program_address(&[proxy.as_ref()], &program_id);
+    let inst = unshield_inst();
+    let keys = [
+        get_associated_token_address(&vault_authority, &mint),
+        unshield_maker,
+        vault_authority,
+        Pubkey::new_unique(),
+        proxy,
+        spl_token::id(),
+        get_associated_token_address(&unshield_maker, &mint),
+        find_burn_tx_address(&program_id, &proxy, &[3; 32]).0,
+        Pubkey::new_unique(),
+        system_program::id(),
+        unshield_maker,
+        mint,
+        spl_associated_token_account::id(),
+        sysvar::rent::id(),
+        find_verified_root_address(&program_id, &proxy, &stored_root).0,
+    ];
+    let token_program = spl_token::id();
+    let system_owner = Pubkey::default();
+    let root_owner = root_owner.unwrap_or(program_id);
+    let mut lamports = [0; 15];
+    let mut data = vec![vec![]; 15];
+    data[0] = token_account_data(mint, vault_authority, 1);
+    // legacy vault without burnt tx ids
+    data[3] = vec![1, 0, 0, 0, 0];
+    data[4] = proxy_data(IncognitoProxy {
+        bump_seed: bump,
+        vault: keys[3],
+        start_height,
+        beacons: Committee::new(4, 1).beacons(),
+        ..proxy_info()
+    });
+    data[14] = stored_root.to_vec();
+    let accounts: Vec<AccountInfo> = keys
+        .iter()
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .enumerate()
+        .map(|(i, ((key, lamports), data))| {
+            let owner = match i {
+                0 => &token_program,
+                3 | 4 => &program_id,
+                14 => &root_owner,
+                _ => &system_owner,
+            };
+            let is_writable = matches!(i, 0 | 6 | 7 | 8);
+            AccountInfo::new(key, i == 8, is_writable, lamports, data, owner, matches!(i, 5 | 9 | 12), 0)
+        })
+        .collect();
+
+    process_instruction(
+        &program_id,
+        &accounts,
+        &BridgeInstruction::UnshieldVerified {
+            unshield_info: VerifiedUnshieldRequest {
+                inst,
+                height,
+                inst_paths: Vec::new(),
+                inst_path_is_lefts: Vec::new(),
+                inst_root,
+            },
+        }
+        .pack(),
+    )
+}
+
+#[test]
+fn test_unshield_verified() {
+    let inst_root = inst_leaf(&unshield_inst(), BEACON_HEIGHT);
+    // signatures are not checked, the unshield goes on to create the burn tx account
+    assert_eq!(
+        process_unshield_verified(BEACON_HEIGHT, inst_root, inst_root, None, 0),
+        Err(ProgramError::UnsupportedSysvar)
+    );
+    assert_eq!(
+        process_unshield_verified(BEACON_HEIGHT, [7; 32], [7; 32], None, 0),
+        Err(BridgeError::InvalidBeaconMerkleTree.into())
+    );
+    // the height is bound by the instruction leaf, not by the verified root account
+    assert_eq!(
+        process_unshield_verified(BEACON_HEIGHT + 1, inst_root, inst_root, None, 0),
+        Err(BridgeError::InvalidBeaconMerkleTree.into())
+    );
+}
+
+#[test]
+fn test_unshield_verified_not_verified() {
+    let inst_root = inst_leaf(&unshield_inst(), BEACON_HEIGHT);
+    assert_eq!(
+        process_unshield_verified(BEACON_HEIGHT, inst_root, inst_root, Some(system_program::id()), 0),
+        Err(BridgeError::BeaconBlockNotVerified.into())
+    );
+    assert_eq!(
+        process_unshield_verified(BEACON_HEIGHT, inst_root, [7; 32], None, 0),
+        Err(BridgeError::InvalidVerifiedRootAccount.into())
+    );
+}
+
+#[test]
+fn test_unshield_verified_before_start_height() {
+    let inst_root = inst_leaf(&unshield_inst(), BEACON_HEIGHT);
+    assert_eq!(
+        process_unshield_verified(BEACON_HEIGHT, inst_root, inst_root, None, BEACON_HEIGHT + 1),
+        Err(BridgeError::UnshieldBeforeStartHeight.into())
+    );
+    assert_eq!(
+        process_unshield_verified(BEACON_HEIGHT, inst_root, inst_root, None, BEACON_HEIGHT),
+        Err(ProgramError::UnsupportedSysvar)
+    );
+}
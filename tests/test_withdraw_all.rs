@@ -0,0 +1,137 @@
+mod helpers;
+
+use std::sync::{Mutex, Once};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+};
+use solana_bridge::{
+    error::BridgeError,
+    instruction::{find_signer_authority_address, get_vault_authority_address, BridgeInstruction},
+    processor::process_instruction,
+    state::IncognitoProxy,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::instruction::TokenInstruction;
+
+use crate::helpers::fixtures::{proxy_data, proxy_info, token_account_data};
+
+static INVOKED: Mutex<Vec<(TokenInstruction, Vec<Pubkey>)>> = Mutex::new(Vec::new());
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+static INIT_STUBS: Once = Once::new();
+
+// records token program calls instead of running them
+struct TokenStubs;
+
+impl SyscallStubs for TokenStubs {
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        _account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        INVOKED.lock().unwrap().push((
+            TokenInstruction::unpack(&instruction.data)?,
+            instruction.accounts.iter().map(|account| account.pubkey).collect(),
+        ));
+        Ok(())
+    }
+}
+
+// sweeps one signer authority token account per amount, returns the signer and swept account keys
+fn process_withdraw_all(
+    amounts: &[u64],
+    close_accounts: bool,
+    signer_authority: Option<Pubkey>,
+) -> (ProgramResult, Pubkey, Vec<Pubkey>) {
+    INIT_STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(TokenStubs));
+    });
+    INVOKED.lock().unwrap().clear();
+
+    let program_id = Pubkey::new_unique();
+    let incognito_proxy = Pubkey::new_unique();
+    let signer = Pubkey::new_unique();
+    let (_, bump_seed) = Pubkey::find_program_address(&[incognito_proxy.as_ref()], &program_id);
+    let vault_authority = get_vault_authority_address(&program_id, &incognito_proxy, bump_seed);
+    let mut keys = vec![
+        incognito_proxy,
+        signer,
+        signer_authority.unwrap_or_else(|| find_signer_authority_address(&program_id, &signer).0),
+        spl_token::ID,
+    ];
+    let mut data = vec![proxy_data(IncognitoProxy { bump_seed, ..proxy_info() }), vec![], vec![], vec![]];
+    let mut owners = vec![program_id, Pubkey::default(), Pubkey::default(), Pubkey::default()];
+    for amount in amounts {
+        let mint = Pubkey::new_unique();
+        keys.push(Pubkey::new_unique());
+        keys.push(get_associated_token_address(&vault_authority, &mint));
+        data.push(token_account_data(mint, Pubkey::new_unique(), *amount));
+        data.push(token_account_data(mint, Pubkey::new_unique(), 0));
+        owners.extend([spl_token::ID, spl_token::ID]);
+    }
+    let mut lamports = vec![0; keys.len()];
+    let accounts: Vec<AccountInfo> = keys
+        .iter()
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .zip(owners.iter())
+        .enumerate()
+        .map(|(i, (((key, lamports), data), owner))| {
+            AccountInfo::new(key, i == 1, i == 1 || i >= 4, lamports, data, owner, i == 3, 0)
+        })
+        .collect();
+
+    let result = process_instruction(
+        &program_id,
+        &accounts,
+        &BridgeInstruction::WithdrawAll { inc_address: [1; 148], close_accounts }.pack(),
+    );
+    let swept = keys[4..].iter().step_by(2).copied().collect();
+    (result, signer, swept)
+}
+
+#[test]
+fn test_withdraw_all_sweeps_balances() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let (result, _, swept) = process_withdraw_all(&[100, 0, 7], false, None);
+    assert_eq!(result, Ok(()));
+
+    let invoked = INVOKED.lock().unwrap();
+    assert_eq!(invoked.len(), 2);
+    assert_eq!(invoked[0].0, TokenInstruction::Transfer { amount: 100 });
+    assert_eq!(invoked[0].1[0], swept[0]);
+    assert_eq!(invoked[1].0, TokenInstruction::Transfer { amount: 7 });
+    assert_eq!(invoked[1].1[0], swept[2]);
+}
+
+#[test]
+fn test_withdraw_all_closes_accounts() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let (result, signer, swept) = process_withdraw_all(&[5, 0], true, None);
+    assert_eq!(result, Ok(()));
+
+    let invoked = INVOKED.lock().unwrap();
+    let closed: Vec<(Pubkey, Pubkey)> = invoked
+        .iter()
+        .filter(|(instruction, _)| *instruction == TokenInstruction::CloseAccount)
+        .map(|(_, accounts)| (accounts[0], accounts[1]))
+        .collect();
+    assert_eq!(invoked.len(), 3);
+    assert_eq!(closed, vec![(swept[0], signer), (swept[1], signer)]);
+}
+
+#[test]
+fn test_withdraw_all_invalid_accounts() {
+    let _lock = TEST_LOCK.lock().unwrap();
+    let (result, _, _) = process_withdraw_all(&[], false, None);
+    assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+
+    let (result, _, _) = process_withdraw_all(&[1], false, Some(Pubkey::new_unique()));
+    assert_eq!(result, Err(BridgeError::InvalidSignerTokenAuth.into()));
+    assert!(INVOKED.lock().unwrap().is_empty());
+}